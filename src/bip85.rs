@@ -0,0 +1,198 @@
+use wasm_bindgen::prelude::*;
+use bip39::{Language, Mnemonic};
+use sha2::Sha512;
+use hmac::{Hmac, Mac};
+use k256::elliptic_curve::PrimeField;
+use k256::{FieldBytes, Scalar};
+
+use crate::{bip39_seed, encode_hex, encode_wif, join_words, parse_language, parse_mnemonic};
+
+// BIP-85: Deterministic Entropy From BIP32 Keychains
+// https://github.com/bitcoin/bips/blob/master/bip-0085.mediawiki
+
+const PURPOSE: u32 = 83696968;
+const APP_BIP39: u32 = 39;
+const APP_WIF: u32 = 2;
+const APP_HEX: u32 = 128169;
+const HARDENED: u32 = 0x8000_0000;
+
+struct ExtendedKey {
+    key: [u8; 32],
+    chain_code: [u8; 32],
+}
+
+fn hmac_sha512(key: &[u8], data: &[&[u8]]) -> [u8; 64] {
+    let mut mac = Hmac::<Sha512>::new_from_slice(key)
+        .expect("HMAC accepts keys of any length");
+    for part in data {
+        mac.update(part);
+    }
+    mac.finalize().into_bytes().into()
+}
+
+fn split_key(bytes: [u8; 64]) -> Result<ExtendedKey, String> {
+    let (key, chain_code) = bytes.split_at(32);
+    let key: [u8; 32] = key.try_into().expect("32-byte half");
+    // Keys must be valid non-zero secp256k1 scalars
+    let scalar = Option::<Scalar>::from(Scalar::from_repr(FieldBytes::from(key)))
+        .ok_or("Derived key is out of range")?;
+    if bool::from(scalar.is_zero()) {
+        return Err("Derived key is zero".to_string());
+    }
+
+    Ok(ExtendedKey {
+        key,
+        chain_code: chain_code.try_into().expect("32-byte half"),
+    })
+}
+
+fn master_key(seed: &[u8]) -> Result<ExtendedKey, String> {
+    split_key(hmac_sha512(b"Bitcoin seed", &[seed]))
+}
+
+// BIP-85 paths are fully hardened, so only private derivation is needed
+fn derive_hardened(parent: &ExtendedKey, index: u32) -> Result<ExtendedKey, String> {
+    let i = hmac_sha512(&parent.chain_code, &[&[0], &parent.key, &(index | HARDENED).to_be_bytes()]);
+    let tweak = split_key(i)?;
+
+    let parent_scalar = Scalar::from_repr(FieldBytes::from(parent.key)).unwrap();
+    let tweak_scalar = Scalar::from_repr(FieldBytes::from(tweak.key)).unwrap();
+    let child: [u8; 32] = (parent_scalar + tweak_scalar).to_repr().into();
+
+    split_key([child, tweak.chain_code].concat().try_into().expect("64 bytes"))
+}
+
+fn derive_entropy(root: &ExtendedKey, path: &[u32]) -> Result<[u8; 64], String> {
+    if path.iter().any(|&index| index >= HARDENED) {
+        return Err("Index must be below 2^31".to_string());
+    }
+
+    let mut node = derive_hardened(root, PURPOSE)?;
+    for &index in path {
+        node = derive_hardened(&node, index)?;
+    }
+
+    Ok(hmac_sha512(b"bip-entropy-from-k", &[&node.key]))
+}
+
+fn bip85_language_code(language: Language) -> u32 {
+    match language {
+        Language::English => 0,
+        Language::Japanese => 1,
+        Language::Korean => 2,
+        Language::Spanish => 3,
+        Language::SimplifiedChinese => 4,
+        Language::TraditionalChinese => 5,
+        Language::French => 6,
+        Language::Italian => 7,
+        Language::Czech => 8,
+        Language::Portuguese => 9,
+    }
+}
+
+fn root_key(mnemonic: &str, passphrase: &str) -> Result<ExtendedKey, JsValue> {
+    let mnemonic = parse_mnemonic(mnemonic)
+        .map_err(|_| JsValue::from_str("Invalid mnemonic"))?;
+    master_key(&bip39_seed(&mnemonic, passphrase))
+        .map_err(|e| JsValue::from_str(&e))
+}
+
+fn child_mnemonic(root: &ExtendedKey, language: Language, word_count: u32, index: u32) -> Result<Mnemonic, String> {
+    let length = match word_count {
+        12 => 16,
+        18 => 24,
+        24 => 32,
+        _ => return Err("Invalid word count (must be 12, 18, or 24)".to_string()),
+    };
+
+    let entropy = derive_entropy(root, &[APP_BIP39, bip85_language_code(language), word_count, index])?;
+    Mnemonic::from_entropy_in(language, &entropy[..length])
+        .map_err(|_| "Failed to generate mnemonic".to_string())
+}
+
+#[wasm_bindgen]
+pub fn bip85_mnemonic(mnemonic: &str, passphrase: &str, lang: &str, word_count: u32, index: u32) -> Result<String, JsValue> {
+    let language = parse_language(lang)
+        .ok_or_else(|| JsValue::from_str("Unsupported language"))?;
+    let root = root_key(mnemonic, passphrase)?;
+
+    let child = child_mnemonic(&root, language, word_count, index)
+        .map_err(|e| JsValue::from_str(&e))?;
+
+    Ok(join_words(language, child.words()))
+}
+
+#[wasm_bindgen]
+pub fn bip85_wif(mnemonic: &str, passphrase: &str, index: u32) -> Result<String, JsValue> {
+    let root = root_key(mnemonic, passphrase)?;
+
+    let entropy = derive_entropy(&root, &[APP_WIF, index])
+        .map_err(|e| JsValue::from_str(&e))?;
+    let key: [u8; 32] = entropy[..32].try_into().expect("32 bytes");
+
+    Ok(encode_wif(&key))
+}
+
+#[wasm_bindgen]
+pub fn bip85_hex(mnemonic: &str, passphrase: &str, num_bytes: u32, index: u32) -> Result<String, JsValue> {
+    if !(16..=64).contains(&num_bytes) {
+        return Err(JsValue::from_str("Invalid length (must be 16 to 64 bytes)"));
+    }
+    let root = root_key(mnemonic, passphrase)?;
+
+    let entropy = derive_entropy(&root, &[APP_HEX, num_bytes, index])
+        .map_err(|e| JsValue::from_str(&e))?;
+
+    Ok(encode_hex(&entropy[..num_bytes as usize]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The test vectors in BIP-85 all start from this root key
+    const ROOT_XPRV: &str = "xprv9s21ZrQH143K2LBWUUQRFXhucrQqBpKdRRxNVq2zBqsx8HVqFk2uYo8kmbaLLHRdqtQpUm98uKfu3vca1LqdGhUtyoFnCNkfmXRyPXLjbKb";
+
+    fn root() -> ExtendedKey {
+        // Version, depth, fingerprint and child number come before the chain
+        // code; the private key is prefixed with a zero byte
+        let bytes = bs58::decode(ROOT_XPRV).into_vec().unwrap();
+        assert_eq!(bytes.len(), 78 + 4);
+        split_key([&bytes[46..78], &bytes[13..45]].concat().try_into().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn derived_entropy() {
+        assert_eq!(
+            encode_hex(&derive_entropy(&root(), &[0, 0]).unwrap()),
+            "efecfbccffea313214232d29e71563d941229afb4338c21f9517c41aaa0d16f00b83d2a09ef747e7a64e8e2bd5a14869e693da66ce94ac2da570ab7ee48618f7"
+        );
+    }
+
+    #[test]
+    fn bip39_application() {
+        let child = child_mnemonic(&root(), Language::English, 12, 0).unwrap();
+        assert_eq!(child.to_string(), "girl mad pet galaxy egg matter matrix prison refuse sense ordinary nose");
+        assert!(child_mnemonic(&root(), Language::English, 15, 0).is_err());
+    }
+
+    #[test]
+    fn wif_application() {
+        let entropy = derive_entropy(&root(), &[APP_WIF, 0]).unwrap();
+        assert_eq!(encode_wif(entropy[..32].try_into().unwrap()), "Kzyv4uF39d4Jrw2W7UryTHwZr1zQVNk4dAFyqE6BuMrMh1Za7uhp");
+    }
+
+    #[test]
+    fn hex_application() {
+        let entropy = derive_entropy(&root(), &[APP_HEX, 64, 0]).unwrap();
+        assert_eq!(
+            encode_hex(&entropy),
+            "492db4698cf3b73a5a24998aa3e9d7fa96275d85724a91e71aa2d645442f878555d078fd1f1f67e368976f04137b1f7a0d19232136ca50c44614af72b5582a5c"
+        );
+    }
+
+    #[test]
+    fn hardened_indices_are_rejected() {
+        assert!(derive_entropy(&root(), &[APP_WIF, HARDENED]).is_err());
+    }
+}
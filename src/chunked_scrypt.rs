@@ -0,0 +1,189 @@
+use wasm_bindgen::prelude::*;
+use js_sys::Function;
+use sha2::Sha256;
+
+use crate::ScryptCost;
+
+// scrypt (RFC 7914) with ROMix split into chunks, handing control back to the
+// event loop between them so the page stays responsive. The output is the
+// same as scrypt::scrypt with the same parameters.
+
+// About a few milliseconds of work at r = 8
+const CHUNK_ITERATIONS: usize = 1024;
+const SALSA_WORDS: usize = 16;
+
+fn salsa20_8(block: &mut [u32; SALSA_WORDS]) {
+    fn quarter(x: &mut [u32; SALSA_WORDS], a: usize, b: usize, c: usize, d: usize) {
+        x[b] ^= x[a].wrapping_add(x[d]).rotate_left(7);
+        x[c] ^= x[b].wrapping_add(x[a]).rotate_left(9);
+        x[d] ^= x[c].wrapping_add(x[b]).rotate_left(13);
+        x[a] ^= x[d].wrapping_add(x[c]).rotate_left(18);
+    }
+
+    let mut x = *block;
+    for _ in 0..4 {
+        // Columns, then rows
+        quarter(&mut x, 0, 4, 8, 12);
+        quarter(&mut x, 5, 9, 13, 1);
+        quarter(&mut x, 10, 14, 2, 6);
+        quarter(&mut x, 15, 3, 7, 11);
+        quarter(&mut x, 0, 1, 2, 3);
+        quarter(&mut x, 5, 6, 7, 4);
+        quarter(&mut x, 10, 11, 8, 9);
+        quarter(&mut x, 15, 12, 13, 14);
+    }
+    for (b, x) in block.iter_mut().zip(x) {
+        *b = b.wrapping_add(x);
+    }
+}
+
+// BlockMix over 2r Salsa blocks, even outputs first, then odd ones
+fn block_mix(input: &[u32], output: &mut [u32], r: usize) {
+    let mut x: [u32; SALSA_WORDS] = input[(2 * r - 1) * SALSA_WORDS..]
+        .try_into()
+        .expect("last Salsa block");
+    for (i, block) in input.chunks_exact(SALSA_WORDS).enumerate() {
+        for (x, b) in x.iter_mut().zip(block) {
+            *x ^= b;
+        }
+        salsa20_8(&mut x);
+        let dest = (i / 2 + (i % 2) * r) * SALSA_WORDS;
+        output[dest..dest + SALSA_WORDS].copy_from_slice(&x);
+    }
+}
+
+fn report(progress: Option<&Function>, fraction: f64) -> Result<(), JsValue> {
+    if let Some(callback) = progress {
+        callback.call1(&JsValue::NULL, &JsValue::from_f64(fraction))?;
+    }
+    Ok(())
+}
+
+// A macrotask, unlike an awaited resolved promise, lets the browser render
+#[cfg(target_arch = "wasm32")]
+async fn next_tick() -> Result<(), JsValue> {
+    use js_sys::Promise;
+    use wasm_bindgen_futures::JsFuture;
+
+    let set_timeout: Function = js_sys::Reflect::get(&js_sys::global(), &JsValue::from_str("setTimeout"))?
+        .dyn_into()?;
+    let promise = Promise::new(&mut |resolve, _| {
+        let _ = set_timeout.call2(&JsValue::NULL, &resolve, &JsValue::from_f64(0.0));
+    });
+    JsFuture::from(promise).await?;
+    Ok(())
+}
+
+// Native builds (the tests) have no event loop to hand control back to
+#[cfg(not(target_arch = "wasm32"))]
+async fn next_tick() -> Result<(), JsValue> {
+    Ok(())
+}
+
+// `progress` receives the completed fraction, from 0 to 1; an exception
+// thrown by it aborts the derivation
+pub(crate) async fn scrypt(
+    password: &[u8],
+    salt: &[u8],
+    cost: &ScryptCost,
+    output: &mut [u8],
+    progress: Option<&Function>,
+) -> Result<(), JsValue> {
+    let n = 1usize << cost.log_n;
+    let r = cost.r as usize;
+    let block_words = 32 * r;
+
+    let mut blocks = vec![0u8; cost.p as usize * 128 * r];
+    pbkdf2::pbkdf2_hmac::<Sha256>(password, salt, 1, &mut blocks);
+
+    let total = 2 * n * cost.p as usize;
+    let mut done = 0;
+    let mut v = vec![0u32; n * block_words];
+    let mut x = vec![0u32; block_words];
+    let mut t = vec![0u32; block_words];
+
+    for block in blocks.chunks_exact_mut(128 * r) {
+        for (word, bytes) in x.iter_mut().zip(block.chunks_exact(4)) {
+            *word = u32::from_le_bytes(bytes.try_into().expect("4 bytes"));
+        }
+
+        // ROMix: fill V with successive BlockMix outputs, then read it back in
+        // the data-dependent order given by Integerify
+        for i in 0..2 * n {
+            if i < n {
+                v[i * block_words..(i + 1) * block_words].copy_from_slice(&x);
+            } else {
+                let j = x[(2 * r - 1) * SALSA_WORDS] as usize & (n - 1);
+                for (x, v) in x.iter_mut().zip(&v[j * block_words..(j + 1) * block_words]) {
+                    *x ^= v;
+                }
+            }
+            block_mix(&x, &mut t, r);
+            std::mem::swap(&mut x, &mut t);
+
+            done += 1;
+            if done % CHUNK_ITERATIONS == 0 && done < total {
+                report(progress, done as f64 / total as f64)?;
+                next_tick().await?;
+            }
+        }
+
+        for (bytes, word) in block.chunks_exact_mut(4).zip(&x) {
+            bytes.copy_from_slice(&word.to_le_bytes());
+        }
+    }
+
+    pbkdf2::pbkdf2_hmac::<Sha256>(password, &blocks, 1, output);
+    report(progress, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::block_on;
+
+    fn chunked(password: &[u8], salt: &[u8], log_n: u8, r: u32, p: u32, length: usize) -> Vec<u8> {
+        let mut output = vec![0u8; length];
+        block_on(scrypt(password, salt, &ScryptCost { log_n, r, p }, &mut output, None)).unwrap();
+        output
+    }
+
+    fn reference(password: &[u8], salt: &[u8], log_n: u8, r: u32, p: u32, length: usize) -> Vec<u8> {
+        let mut output = vec![0u8; length];
+        let params = ::scrypt::Params::new(log_n, r, p, length).unwrap();
+        ::scrypt::scrypt(password, salt, &params, &mut output).unwrap();
+        output
+    }
+
+    #[test]
+    fn rfc_7914_vector() {
+        assert_eq!(
+            crate::encode_hex(&chunked(b"", b"", 4, 1, 1, 64)),
+            "77d6576238657b203b19ca42c18a0497f16b4844e3074ae8dfdffa3fede21442fcd0069ded0948f8326a753a0fc81f17e8d3e0fb2e0d3628cf35e20c38d18906"
+        );
+    }
+
+    #[test]
+    fn matches_scrypt_crate() {
+        for (log_n, r, p) in [(5, 1, 1), (5, 3, 7), (8, 8, 1), (8, 2, 3), (9, 1, 1), (9, 8, 2)] {
+            assert_eq!(
+                chunked(b"password", b"salt", log_n, r, p, 32),
+                reference(b"password", b"salt", log_n, r, p, 32),
+                "log_n {} r {} p {}",
+                log_n,
+                r,
+                p
+            );
+        }
+    }
+
+    // The cost every key is derived with, spanning many chunks
+    #[test]
+    fn matches_scrypt_crate_at_default_cost() {
+        let cost = crate::DEFAULT_SCRYPT_COST;
+        assert_eq!(
+            chunked(&[0x5a; 64], crate::MASTER_KEY_SALT, cost.log_n, cost.r, cost.p, 32),
+            reference(&[0x5a; 64], crate::MASTER_KEY_SALT, cost.log_n, cost.r, cost.p, 32)
+        );
+    }
+}
@@ -0,0 +1,588 @@
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::future_to_promise;
+use js_sys::Promise;
+
+use crate::{generate_entropy, scheme};
+
+// Codex32: checksummed, hand-computable Shamir shares of a BIP-32 master seed
+// https://github.com/bitcoin/bips/blob/master/bip-0093.mediawiki
+//
+// A string is "ms1" followed by the threshold (0 or 2-9), a 4 character
+// identifier, the share index ("s" is the secret), the payload and a BCH
+// checksum over GF(32). Shares are combined character by character, header
+// and checksum included, so the recovered secret carries a valid checksum.
+
+const HRP: &str = "ms";
+const CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+const HEADER_LENGTH: usize = 6;
+const IDENTIFIER_LENGTH: usize = 4;
+const SECRET_INDEX: u8 = 16;
+// Every character except "s", in the order shares are handed out
+const SHARE_INDICES: [u8; 31] = char_values(b"acdefghjklmnpqrtuvwxyz023456789");
+const MIN_SECRET_BYTES: usize = 16;
+const MAX_SECRET_BYTES: usize = 64;
+// Checksum search is exhaustive, so keep the number of "?" placeholders small
+const MAX_UNKNOWN_CHARS: usize = 3;
+
+const fn build_tables() -> ([u8; 31], [u8; 32]) {
+    let mut exp = [0u8; 31];
+    let mut log = [0u8; 32];
+    let mut value: u8 = 1;
+    let mut i = 0;
+    while i < 31 {
+        exp[i] = value;
+        log[value as usize] = i as u8;
+        // Multiply by the generator x, reducing by x^5 + x^3 + 1
+        value <<= 1;
+        if value & 32 != 0 {
+            value ^= 0x29;
+        }
+        i += 1;
+    }
+    (exp, log)
+}
+
+const TABLES: ([u8; 31], [u8; 32]) = build_tables();
+const EXP: [u8; 31] = TABLES.0;
+const LOG: [u8; 32] = TABLES.1;
+
+fn mul(a: u8, b: u8) -> u8 {
+    if a == 0 || b == 0 {
+        return 0;
+    }
+    EXP[(LOG[a as usize] as usize + LOG[b as usize] as usize) % 31]
+}
+
+fn inv(a: u8) -> u8 {
+    EXP[(31 - LOG[a as usize] as usize) % 31]
+}
+
+const fn char_values<const N: usize>(chars: &[u8; N]) -> [u8; N] {
+    let mut values = [0u8; N];
+    let mut i = 0;
+    while i < N {
+        let mut value = 0;
+        while CHARSET[value] != chars[i] {
+            value += 1;
+        }
+        values[i] = value as u8;
+        i += 1;
+    }
+    values
+}
+
+fn char_value(c: char) -> Option<u8> {
+    let c = c.to_ascii_lowercase();
+    CHARSET.iter().position(|&b| b as char == c).map(|value| value as u8)
+}
+
+struct Bch {
+    generator: &'static [u8],
+    target: &'static [u8],
+}
+
+// The short checksum covers strings of 48 to 93 characters, the long one 125 to 127
+const SHORT_CHECKSUM: Bch = Bch {
+    generator: &char_values(b"em3gqeeelmcss"),
+    target: &char_values(b"secretshare32"),
+};
+const LONG_CHECKSUM: Bch = Bch {
+    generator: &char_values(b"02e6fe4xh4x9kyh"),
+    target: &char_values(b"secretshare32ex"),
+};
+
+fn checksum_for(string_length: usize) -> Option<&'static Bch> {
+    match string_length {
+        48..=93 => Some(&SHORT_CHECKSUM),
+        125..=127 => Some(&LONG_CHECKSUM),
+        _ => None,
+    }
+}
+
+fn residue(bch: &Bch, data: &[u8]) -> Vec<u8> {
+    let n = bch.generator.len();
+    let mut residue = vec![0u8; n];
+    residue[n - 1] = 1;
+
+    let hrp = HRP.bytes().map(|c| c >> 5).chain([0]).chain(HRP.bytes().map(|c| c & 31));
+    for value in hrp.chain(data.iter().copied()) {
+        let top = residue[0];
+        residue.rotate_left(1);
+        residue[n - 1] = value;
+        for (r, &g) in residue.iter_mut().zip(bch.generator) {
+            *r ^= mul(g, top);
+        }
+    }
+    residue
+}
+
+fn checksum_valid(bch: &Bch, data: &[u8]) -> bool {
+    residue(bch, data) == bch.target
+}
+
+fn bytes_to_values(bytes: &[u8]) -> Vec<u8> {
+    let mut values = Vec::with_capacity((bytes.len() * 8).div_ceil(5));
+    let mut acc: u32 = 0;
+    let mut bits = 0;
+    for &byte in bytes {
+        acc = (acc << 8) | byte as u32;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            values.push(((acc >> bits) & 31) as u8);
+        }
+    }
+    // Padding bits are zero here but may be anything in a valid string
+    if bits > 0 {
+        values.push(((acc << (5 - bits)) & 31) as u8);
+    }
+    values
+}
+
+fn values_to_bytes(values: &[u8]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(values.len() * 5 / 8);
+    let mut acc: u32 = 0;
+    let mut bits = 0;
+    for &value in values {
+        acc = (acc << 5) | value as u32;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((acc >> bits) as u8);
+        }
+    }
+    bytes
+}
+
+fn to_string(values: &[u8], uppercase: bool) -> String {
+    let string: String = format!("{}1", HRP)
+        .chars()
+        .chain(values.iter().map(|&v| CHARSET[v as usize] as char))
+        .collect();
+    if uppercase { string.to_ascii_uppercase() } else { string }
+}
+
+// Data part values of a string, with None for "?" placeholders when allowed
+fn decode_chars(string: &str, allow_unknown: bool) -> Result<(Vec<Option<u8>>, bool), String> {
+    let string = string.trim();
+    let has_lower = string.chars().any(|c| c.is_ascii_lowercase());
+    let has_upper = string.chars().any(|c| c.is_ascii_uppercase());
+    if has_lower && has_upper {
+        return Err("Codex32 strings must not mix upper and lower case".to_string());
+    }
+
+    let prefix = format!("{}1", HRP);
+    let data = string
+        .get(..prefix.len())
+        .filter(|start| start.eq_ignore_ascii_case(&prefix))
+        .map(|_| &string[prefix.len()..])
+        .ok_or_else(|| "Codex32 strings must start with \"ms1\"".to_string())?;
+
+    let values = data
+        .chars()
+        .enumerate()
+        .map(|(i, c)| match char_value(c) {
+            Some(value) => Ok(Some(value)),
+            None if c == '?' && allow_unknown => Ok(None),
+            None => Err(format!("Invalid character '{}' at position {}", c, i + prefix.len() + 1)),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok((values, has_upper))
+}
+
+struct Share {
+    threshold: usize,
+    identifier: Vec<u8>,
+    index: u8,
+    // The whole data part, header and checksum included
+    values: Vec<u8>,
+}
+
+impl Share {
+    fn from_values(values: Vec<u8>) -> Result<Share, String> {
+        let bch = checksum_for(values.len() + HRP.len() + 1)
+            .ok_or_else(|| "Invalid length (must be 48 to 93, or 125 to 127 characters)".to_string())?;
+        if !checksum_valid(bch, &values) {
+            return Err("Invalid checksum".to_string());
+        }
+
+        let threshold = match CHARSET[values[0] as usize] {
+            b'0' => 1,
+            c @ b'2'..=b'9' => (c - b'0') as usize,
+            _ => return Err("Invalid threshold (must be 0 or 2 to 9)".to_string()),
+        };
+        let index = values[HEADER_LENGTH - 1];
+        if threshold == 1 && index != SECRET_INDEX {
+            return Err("A threshold of 0 requires share index \"s\"".to_string());
+        }
+
+        let payload_length = values.len() - HEADER_LENGTH - bch.generator.len();
+        if payload_length * 5 % 8 > 4 {
+            return Err("Invalid payload length".to_string());
+        }
+
+        Ok(Share {
+            threshold,
+            identifier: values[1..HEADER_LENGTH - 1].to_vec(),
+            index,
+            values,
+        })
+    }
+
+    fn parse(string: &str) -> Result<Share, String> {
+        let (values, _) = decode_chars(string, false)?;
+        Share::from_values(values.into_iter().flatten().collect())
+    }
+}
+
+fn payload_bytes(values: &[u8]) -> Vec<u8> {
+    let checksum_length = checksum_for(values.len() + HRP.len() + 1)
+        .map_or(0, |bch| bch.generator.len());
+    values_to_bytes(&values[HEADER_LENGTH..values.len() - checksum_length])
+}
+
+// A threshold of 1 is written as "0" and only allowed for the secret itself
+fn encode(threshold: u8, identifier: &[u8], index: u8, payload: &[u8]) -> Result<Vec<u8>, String> {
+    let threshold_char = if threshold == 1 { '0' } else { (b'0' + threshold) as char };
+    let mut values = vec![char_value(threshold_char).ok_or_else(|| "Invalid threshold".to_string())?];
+    values.extend_from_slice(identifier);
+    values.push(index);
+    values.extend(bytes_to_values(payload));
+
+    // Long strings only fit 63 and 64 byte secrets, shorter ones up to 44 bytes
+    let length = HRP.len() + 1 + values.len();
+    let bch = if length + SHORT_CHECKSUM.generator.len() <= 93 { &SHORT_CHECKSUM } else { &LONG_CHECKSUM };
+    if checksum_for(length + bch.generator.len()).is_none() {
+        return Err(format!("Unsupported master secret length of {} bytes", payload.len()));
+    }
+
+    let mut padded = values.clone();
+    padded.extend_from_slice(bch.target);
+    values.extend(residue(bch, &padded));
+    Ok(values)
+}
+
+fn parse_identifier(identifier: Option<String>) -> Result<Vec<u8>, String> {
+    match identifier {
+        Some(identifier) => {
+            let values: Option<Vec<u8>> = identifier.trim().chars().map(char_value).collect();
+            values
+                .filter(|values| values.len() == IDENTIFIER_LENGTH)
+                .ok_or_else(|| "Identifier must be 4 bech32 characters".to_string())
+        }
+        None => Ok(bytes_to_values(&generate_entropy(3, None))[..IDENTIFIER_LENGTH].to_vec()),
+    }
+}
+
+fn check_secret_length(master_secret: &[u8]) -> Result<(), String> {
+    if (MIN_SECRET_BYTES..=MAX_SECRET_BYTES).contains(&master_secret.len()) {
+        Ok(())
+    } else {
+        Err(format!("Master secret must be {} to {} bytes", MIN_SECRET_BYTES, MAX_SECRET_BYTES))
+    }
+}
+
+// Lagrange interpolation over GF(32) of every character position at `x`
+fn interpolate(shares: &[&Share], x: u8) -> Vec<u8> {
+    let mut result = vec![0u8; shares[0].values.len()];
+    for share in shares {
+        let mut weight = 1;
+        for other in shares {
+            if other.index != share.index {
+                weight = mul(weight, mul(x ^ other.index, inv(share.index ^ other.index)));
+            }
+        }
+        for (acc, &value) in result.iter_mut().zip(&share.values) {
+            *acc ^= mul(weight, value);
+        }
+    }
+    result
+}
+
+fn split_secret(master_secret: &[u8], identifier: &[u8], threshold: u8, share_count: u8) -> Result<Vec<Vec<u8>>, String> {
+    check_secret_length(master_secret)?;
+    if !(2..=9).contains(&threshold) || share_count < threshold || share_count as usize > SHARE_INDICES.len() {
+        return Err(format!("Invalid threshold {} of {} shares (need 2 <= threshold <= 9 and threshold <= shares <= 31)", threshold, share_count));
+    }
+
+    // threshold - 1 random shares plus the secret fix the polynomial
+    let mut base = vec![Share::from_values(encode(threshold, identifier, SECRET_INDEX, master_secret)?)?];
+    for i in 0..threshold - 1 {
+        let random = generate_entropy(master_secret.len(), None);
+        base.push(Share::from_values(encode(threshold, identifier, SHARE_INDICES[i as usize], &random)?)?);
+    }
+    let base: Vec<&Share> = base.iter().collect();
+
+    let mut shares: Vec<Vec<u8>> = base[1..].iter().map(|share| share.values.clone()).collect();
+    for i in threshold - 1..share_count {
+        shares.push(interpolate(&base, SHARE_INDICES[i as usize]));
+    }
+    Ok(shares)
+}
+
+fn combine_shares(strings: &[String]) -> Result<Vec<u8>, String> {
+    let shares = strings
+        .iter()
+        .enumerate()
+        .map(|(i, string)| Share::parse(string).map_err(|e| format!("Share {}: {}", i + 1, e)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let Some(first) = shares.first() else {
+        return Err("No shares provided".to_string());
+    };
+    if shares.iter().any(|s| s.threshold != first.threshold || s.identifier != first.identifier || s.values.len() != first.values.len()) {
+        return Err("Shares come from different secrets".to_string());
+    }
+
+    let mut unique: Vec<&Share> = Vec::new();
+    for share in &shares {
+        match unique.iter().find(|s| s.index == share.index) {
+            Some(other) if other.values != share.values => {
+                return Err(format!("Conflicting shares with index \"{}\"", CHARSET[share.index as usize] as char));
+            }
+            Some(_) => {}
+            None => unique.push(share),
+        }
+    }
+
+    if let Some(secret) = unique.iter().find(|s| s.index == SECRET_INDEX) {
+        return Ok(payload_bytes(&secret.values));
+    }
+    if unique.len() < first.threshold {
+        return Err(format!("Not enough shares: {} of {} required", unique.len(), first.threshold));
+    }
+
+    let (base, surplus) = unique.split_at(first.threshold);
+    if let Some(share) = surplus.iter().find(|s| interpolate(base, s.index) != s.values) {
+        return Err(format!("Share with index \"{}\" does not match the others", CHARSET[share.index as usize] as char));
+    }
+
+    Ok(payload_bytes(&interpolate(base, SECRET_INDEX)))
+}
+
+#[derive(serde::Serialize)]
+struct Codex32Correction {
+    string: String,
+    // Positions in the full string, counting from 0
+    positions: Vec<usize>,
+}
+
+#[derive(serde::Serialize)]
+struct Codex32Check {
+    valid: bool,
+    error: Option<String>,
+    corrections: Vec<Codex32Correction>,
+}
+
+// Strings that differ from the input in one character (or fill in every "?")
+// and carry a valid checksum and header. The checksum guarantees at most one
+// single-substitution fix, so several results mean more than one error.
+fn correction_candidates(values: &[Option<u8>], uppercase: bool) -> Vec<Codex32Correction> {
+    let offset = HRP.len() + 1;
+    if checksum_for(values.len() + offset).is_none() {
+        return Vec::new();
+    }
+
+    let unknown: Vec<usize> = (0..values.len()).filter(|&i| values[i].is_none()).collect();
+    let mut current: Vec<u8> = values.iter().map(|v| v.unwrap_or(0)).collect();
+    let mut candidates = Vec::new();
+    let mut push_if_valid = |current: &[u8], positions: Vec<usize>| {
+        if Share::from_values(current.to_vec()).is_ok() {
+            candidates.push(Codex32Correction {
+                string: to_string(current, uppercase),
+                positions: positions.iter().map(|p| p + offset).collect(),
+            });
+        }
+    };
+
+    if unknown.is_empty() {
+        for position in 0..current.len() {
+            let original = current[position];
+            for value in (0..32).filter(|&v| v != original) {
+                current[position] = value;
+                push_if_valid(&current, vec![position]);
+            }
+            current[position] = original;
+        }
+    } else if unknown.len() <= MAX_UNKNOWN_CHARS {
+        for combination in 0..1u32 << (5 * unknown.len()) {
+            for (i, &position) in unknown.iter().enumerate() {
+                current[position] = ((combination >> (5 * i)) & 31) as u8;
+            }
+            push_if_valid(&current, unknown.clone());
+        }
+    }
+
+    candidates
+}
+
+fn check_string(string: &str) -> Codex32Check {
+    let (values, uppercase) = match decode_chars(string, true) {
+        Ok(decoded) => decoded,
+        Err(e) => return Codex32Check { valid: false, error: Some(e), corrections: Vec::new() },
+    };
+
+    let error = if values.contains(&None) {
+        "Unreadable characters".to_string()
+    } else {
+        match Share::from_values(values.iter().flatten().copied().collect()) {
+            Ok(_) => return Codex32Check { valid: true, error: None, corrections: Vec::new() },
+            Err(e) => e,
+        }
+    };
+
+    Codex32Check {
+        valid: false,
+        error: Some(error),
+        corrections: correction_candidates(&values, uppercase),
+    }
+}
+
+fn parse_shares(shares: JsValue) -> Result<Vec<String>, JsValue> {
+    serde_wasm_bindgen::from_value(shares)
+        .map_err(|_| JsValue::from_str("Shares must be an array of strings"))
+}
+
+#[wasm_bindgen]
+pub fn encode_codex32_secret(master_secret: &[u8], identifier: Option<String>) -> Result<String, JsValue> {
+    check_secret_length(master_secret).map_err(|e| JsValue::from_str(&e))?;
+    let identifier = parse_identifier(identifier).map_err(|e| JsValue::from_str(&e))?;
+
+    let values = encode(1, &identifier, SECRET_INDEX, master_secret)
+        .map_err(|e| JsValue::from_str(&e))?;
+
+    Ok(to_string(&values, false))
+}
+
+#[wasm_bindgen]
+pub fn split_codex32_secret(master_secret: &[u8], identifier: Option<String>, threshold: u8, share_count: u8) -> Result<JsValue, JsValue> {
+    let identifier = parse_identifier(identifier).map_err(|e| JsValue::from_str(&e))?;
+
+    let shares: Vec<String> = split_secret(master_secret, &identifier, threshold, share_count)
+        .map_err(|e| JsValue::from_str(&e))?
+        .iter()
+        .map(|values| to_string(values, false))
+        .collect();
+
+    serde_wasm_bindgen::to_value(&shares)
+        .map_err(|_| JsValue::from_str("Serialization failed"))
+}
+
+#[wasm_bindgen]
+pub fn check_codex32_string(string: &str) -> Result<JsValue, JsValue> {
+    serde_wasm_bindgen::to_value(&check_string(string))
+        .map_err(|_| JsValue::from_str("Serialization failed"))
+}
+
+#[wasm_bindgen]
+pub fn combine_codex32_shares(shares: JsValue) -> Result<Vec<u8>, JsValue> {
+    combine_shares(&parse_shares(shares)?).map_err(|e| JsValue::from_str(&e))
+}
+
+#[wasm_bindgen]
+pub fn codex32_to_base58_master_key(shares: JsValue, scheme: Option<String>, account: Option<String>) -> Promise {
+    future_to_promise(async move {
+        let master_secret = combine_shares(&parse_shares(shares)?)
+            .map_err(|e| JsValue::from_str(&e))?;
+
+        // Like SLIP-39, the master secret takes the place of the BIP-39 seed
+        let wif = scheme::stretch_to_wif(&master_secret, scheme.as_deref(), account.as_deref()).await?;
+
+        Ok(JsValue::from_str(&wif))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // From the BIP-93 test vectors
+    const VECTOR_1: &str = "ms10testsxxxxxxxxxxxxxxxxxxxxxxxxxx4nzvca9cmczlw";
+    const VECTOR_2: [&str; 2] = [
+        "MS12NAMEA320ZYXWVUTSRQPNMLKJHGFEDCAXRPP870HKKQRM",
+        "MS12NAMECACDEFGHJKLMNPQRSTUVWXYZ023FTR2GDZMPY6PN",
+    ];
+    const VECTOR_5: &str = "MS100C8VSM32ZXFGUHPCHTLUPZRY9X8GF2TVDW0S3JN54KHCE6MUA7LQPZYGSFJD6AN074RXVCEMLH8WU3TK925ACDEFGHJKLMNPQRSTUVWXY06FHPV80UNDVARHRAK";
+
+    fn combine(strings: &[&str]) -> Result<String, String> {
+        let strings: Vec<String> = strings.iter().map(|s| s.to_string()).collect();
+        combine_shares(&strings).map(|secret| crate::encode_hex(&secret))
+    }
+
+    #[test]
+    fn vector_1() {
+        assert_eq!(combine(&[VECTOR_1]), Ok("318c6318c6318c6318c6318c6318c631".to_string()));
+        assert!(check_string(VECTOR_1).valid);
+    }
+
+    #[test]
+    fn vector_2() {
+        assert_eq!(combine(&VECTOR_2), Ok("d1808e096b35b209ca12132b264662a5".to_string()));
+
+        let shares: Vec<Share> = VECTOR_2.iter().map(|s| Share::parse(s).unwrap()).collect();
+        let shares: Vec<&Share> = shares.iter().collect();
+        let index_d = char_value('d').unwrap();
+        assert_eq!(to_string(&interpolate(&shares, index_d), true), "MS12NAMEDLL4F8JLH4E5VDVULDLFXU2JHDNLSM97XVENRXEG");
+        assert_eq!(to_string(&interpolate(&shares, SECRET_INDEX), true), "MS12NAMES6XQGUZTTXKEQNJSJZV4JV3NZ5K3KWGSPHUH6EVW");
+
+        assert!(combine(&VECTOR_2[..1]).is_err());
+    }
+
+    #[test]
+    fn long_checksum() {
+        let secret = combine(&[VECTOR_5]).unwrap();
+        assert_eq!(
+            secret,
+            "dc5423251cb87175ff8110c8531d0952d8d73e1194e95b5f19d6f9df7c01111104c9baecdfea8cccc677fb9ddc8aec5553b86e528bcadfdcc201c17c638c47e9"
+        );
+
+        let identifier = parse_identifier(Some("0c8v".to_string())).unwrap();
+        let values = encode(1, &identifier, SECRET_INDEX, &crate::decode_hex(&secret).unwrap()).unwrap();
+        let encoded = to_string(&values, true);
+        assert_eq!(encoded.len(), VECTOR_5.len());
+        assert_eq!(checksum_for(encoded.len()).unwrap().target.len(), LONG_CHECKSUM.target.len());
+        // The vector sets its 3 padding bits, the encoder leaves them zero
+        let data_length = VECTOR_5.len() - LONG_CHECKSUM.target.len() - 1;
+        assert_eq!(encoded[..data_length], VECTOR_5[..data_length]);
+        assert_eq!(combine(&[&encoded]), Ok(secret));
+    }
+
+    #[test]
+    fn split_round_trip() {
+        let secret: Vec<u8> = (0..32).map(|i| i * 7).collect();
+        let identifier = parse_identifier(Some("cash".to_string())).unwrap();
+        let shares: Vec<String> = split_secret(&secret, &identifier, 3, 5)
+            .unwrap()
+            .iter()
+            .map(|values| to_string(values, false))
+            .collect();
+        assert_eq!(shares.len(), 5);
+        assert!(shares.iter().all(|share| share.starts_with("ms13cash")));
+
+        assert_eq!(combine_shares(&shares[2..]).unwrap(), secret);
+        assert_eq!(combine_shares(&[shares[4].clone(), shares[0].clone(), shares[3].clone()]).unwrap(), secret);
+        assert!(combine_shares(&shares[..2]).is_err());
+
+        assert!(split_secret(&secret, &identifier, 1, 5).is_err());
+        assert!(split_secret(&secret, &identifier, 3, 2).is_err());
+        assert!(split_secret(&secret[..15], &identifier, 2, 3).is_err());
+    }
+
+    #[test]
+    fn single_substitution_is_corrected() {
+        let typo = VECTOR_1.replace("4nzv", "4nxv");
+        let check = check_string(&typo);
+        assert!(!check.valid);
+        assert_eq!(check.corrections.len(), 1);
+        assert_eq!(check.corrections[0].string, VECTOR_1);
+        assert_eq!(check.corrections[0].positions, [typo.find("xv").unwrap()]);
+
+        // Unreadable characters are filled in
+        let erased = VECTOR_1.replacen("4nzv", "4n?v", 1);
+        let check = check_string(&erased);
+        assert_eq!(check.error.as_deref(), Some("Unreadable characters"));
+        assert_eq!(check.corrections.len(), 1);
+        assert_eq!(check.corrections[0].string, VECTOR_1);
+    }
+}
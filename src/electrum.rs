@@ -0,0 +1,217 @@
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::future_to_promise;
+use bip39::{Language, Mnemonic};
+use sha2::Sha512;
+use hmac::{Hmac, Mac};
+use rand::rngs::OsRng;
+use rand::Rng;
+use unicode_normalization::UnicodeNormalization;
+use unicode_normalization::char::canonical_combining_class;
+use js_sys::Promise;
+
+use crate::scheme;
+
+// Electrum seeds carry their version in the HMAC of the phrase instead of a
+// BIP-39 checksum; the hex digest must start with one of these prefixes
+const SEED_PREFIXES: [(&str, &str); 4] = [
+    ("standard", "01"),
+    ("segwit", "100"),
+    ("2fa", "101"),
+    ("2fa_segwit", "102"),
+];
+
+// Same ranges as Electrum's CJK_INTERVALS, used to drop spaces between CJK characters
+const CJK_INTERVALS: [(u32, u32); 29] = [
+    (0x4E00, 0x9FFF),
+    (0x3400, 0x4DBF),
+    (0x20000, 0x2A6DF),
+    (0x2A700, 0x2B73F),
+    (0x2B740, 0x2B81F),
+    (0xF900, 0xFAFF),
+    (0x2F800, 0x2FA1D),
+    (0x3190, 0x319F),
+    (0x2E80, 0x2EFF),
+    (0x2F00, 0x2FDF),
+    (0x31C0, 0x31EF),
+    (0x2FF0, 0x2FFF),
+    (0xE0100, 0xE01EF),
+    (0x3100, 0x312F),
+    (0x31A0, 0x31BF),
+    (0xFF00, 0xFFEF),
+    (0x3040, 0x309F),
+    (0x30A0, 0x30FF),
+    (0x31F0, 0x31FF),
+    (0x1B000, 0x1B0FF),
+    (0xAC00, 0xD7AF),
+    (0x1100, 0x11FF),
+    (0xA960, 0xA97F),
+    (0xD7B0, 0xD7FF),
+    (0x3130, 0x318F),
+    (0xA4D0, 0xA4FF),
+    (0x16F00, 0x16F9F),
+    (0xA000, 0xA48F),
+    (0xA490, 0xA4CF),
+];
+
+fn is_cjk(c: char) -> bool {
+    CJK_INTERVALS.iter().any(|&(start, end)| (start..=end).contains(&(c as u32)))
+}
+
+// Port of Electrum's normalize_text: NFKD, lowercase, strip accents, collapse
+// whitespace and remove spaces between CJK characters
+fn normalize_text(text: &str) -> String {
+    let stripped: String = text
+        .nfkd()
+        .collect::<String>()
+        .to_lowercase()
+        .chars()
+        .filter(|&c| canonical_combining_class(c) == 0)
+        .collect();
+
+    let chars: Vec<char> = stripped.split_whitespace().collect::<Vec<_>>().join(" ").chars().collect();
+    chars
+        .iter()
+        .enumerate()
+        .filter(|&(i, &c)| {
+            !(c == ' ' && i > 0 && i + 1 < chars.len() && is_cjk(chars[i - 1]) && is_cjk(chars[i + 1]))
+        })
+        .map(|(_, &c)| c)
+        .collect()
+}
+
+fn seed_version(normalized: &str) -> String {
+    let mut mac = Hmac::<Sha512>::new_from_slice(b"Seed version")
+        .expect("HMAC accepts keys of any length");
+    mac.update(normalized.as_bytes());
+    crate::encode_hex(&mac.finalize().into_bytes())
+}
+
+pub(crate) fn seed_type(phrase: &str) -> Option<&'static str> {
+    let version = seed_version(&normalize_text(phrase));
+    SEED_PREFIXES
+        .iter()
+        .find(|(_, prefix)| version.starts_with(prefix))
+        .map(|(name, _)| *name)
+}
+
+fn electrum_seed(phrase: &str, passphrase: &str) -> [u8; 64] {
+    // Electrum normalizes the passphrase the same way as the phrase (lowercase included)
+    let salt = format!("electrum{}", normalize_text(passphrase));
+    let mut seed = [0u8; 64];
+    pbkdf2::pbkdf2_hmac::<Sha512>(normalize_text(phrase).as_bytes(), salt.as_bytes(), 2048, &mut seed);
+    seed
+}
+
+fn generate_seed(prefix: &str) -> String {
+    let word_list = Language::English.word_list();
+
+    // 132 bits as 12 little-endian base-2048 digits, like Electrum's mnemonic_encode;
+    // a non-zero top digit keeps the phrase at 12 words
+    let mut digits = [0u16; 12];
+    for digit in digits.iter_mut() {
+        *digit = OsRng.gen_range(0..2048);
+    }
+    digits[11] = OsRng.gen_range(1..2048);
+
+    loop {
+        // Electrum increments the entropy by a nonce until the version matches
+        for digit in digits.iter_mut() {
+            *digit = (*digit + 1) % 2048;
+            if *digit != 0 {
+                break;
+            }
+        }
+        if digits[11] == 0 {
+            digits[11] = 1;
+        }
+
+        let phrase = digits.iter().map(|&d| word_list[d as usize]).collect::<Vec<_>>().join(" ");
+
+        // Make sure the seed is not also a valid BIP-39 phrase by accident
+        if Mnemonic::parse_in_normalized(Language::English, &phrase).is_ok() {
+            continue;
+        }
+        if seed_version(&normalize_text(&phrase)).starts_with(prefix) {
+            return phrase;
+        }
+    }
+}
+
+#[wasm_bindgen]
+pub fn generate_electrum_seed(seed_type: &str) -> Result<String, JsValue> {
+    let prefix = SEED_PREFIXES
+        .iter()
+        .find(|(name, _)| *name == seed_type.to_lowercase())
+        .map(|(_, prefix)| *prefix)
+        .ok_or_else(|| JsValue::from_str("Unsupported seed type. Supported: standard, segwit, 2fa, 2fa_segwit."))?;
+
+    Ok(generate_seed(prefix))
+}
+
+#[wasm_bindgen]
+pub fn electrum_seed_type(phrase: &str) -> Option<String> {
+    seed_type(phrase).map(String::from)
+}
+
+#[wasm_bindgen]
+pub fn electrum_to_base58_master_key(
+    mnemonic: &str,
+    passphrase: &str,
+    scheme: Option<String>,
+    account: Option<String>,
+) -> Promise {
+    let mnemonic_str = mnemonic.to_string();
+    let passphrase_str = passphrase.to_string();
+
+    future_to_promise(async move {
+        if seed_type(&mnemonic_str).is_none() {
+            return Err(JsValue::from_str("Not an Electrum seed"));
+        }
+
+        let seed = electrum_seed(&mnemonic_str, &passphrase_str);
+        let wif = scheme::stretch_to_wif(&seed, scheme.as_deref(), account.as_deref()).await?;
+
+        Ok(JsValue::from_str(&wif))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // From Electrum's test_mnemonic.py
+    #[test]
+    fn segwit_vector() {
+        let phrase = "wild father tree among universe such mobile favorite target dynamic credit identify";
+        assert_eq!(seed_type(phrase), Some("segwit"));
+        assert_eq!(
+            crate::encode_hex(&electrum_seed(phrase, "")),
+            "aac2a6302e48577ab4b46f23dbae0774e2e62c796f797d0a1b5faeb528301e3064342dafb79069e7c4c6b8c38ae11d7a973bec0d4f70626f8cc5184a8d0b0756"
+        );
+    }
+
+    // The key electrum_to_base58_master_key gave before it took a scheme tag
+    #[test]
+    fn v1_key_is_unchanged() {
+        let phrase = "wild father tree among universe such mobile favorite target dynamic credit identify";
+        let seed = electrum_seed(phrase, "");
+        let wif = crate::tests::block_on(scheme::stretch_to_wif(&seed, None, None)).unwrap();
+        assert_eq!(wif, "KzfR8Ki8KCNDueCM3UaMfsh4phzuo6FSstGXmiFLfsHExYTKm9ic");
+    }
+
+    #[test]
+    fn generated_seeds_have_their_type() {
+        for (name, prefix) in SEED_PREFIXES {
+            let phrase = generate_seed(prefix);
+            assert_eq!(phrase.split(' ').count(), 12);
+            assert_eq!(seed_type(&phrase), Some(name));
+            assert!(Mnemonic::parse_in_normalized(Language::English, &phrase).is_err());
+        }
+    }
+
+    #[test]
+    fn bip39_phrase_is_not_electrum() {
+        let phrase = "legal winner thank year wave sausage worth useful legal winner thank yellow";
+        assert_eq!(seed_type(phrase), None);
+    }
+}
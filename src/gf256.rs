@@ -0,0 +1,64 @@
+// Arithmetic in GF(256) with the Rijndael polynomial x^8 + x^4 + x^3 + x + 1,
+// as used by SLIP-39, for Shamir secret sharing over byte strings.
+
+const fn build_tables() -> ([u8; 255], [u8; 256]) {
+    let mut exp = [0u8; 255];
+    let mut log = [0u8; 256];
+    let mut poly: u16 = 1;
+    let mut i = 0;
+    while i < 255 {
+        exp[i] = poly as u8;
+        log[poly as usize] = i as u8;
+        // Multiply by the generator 3 = x + 1
+        poly = (poly << 1) ^ poly;
+        if poly & 0x100 != 0 {
+            poly ^= 0x11B;
+        }
+        i += 1;
+    }
+    (exp, log)
+}
+
+const TABLES: ([u8; 255], [u8; 256]) = build_tables();
+const EXP: [u8; 255] = TABLES.0;
+const LOG: [u8; 256] = TABLES.1;
+
+// Lagrange interpolation of the polynomials defined by `shares` evaluated at `x`,
+// independently for every byte position.
+pub(crate) fn interpolate(shares: &[(u8, Vec<u8>)], x: u8) -> Result<Vec<u8>, String> {
+    let Some((_, first)) = shares.first() else {
+        return Err("No shares provided".to_string());
+    };
+    let length = first.len();
+
+    for (i, (xi, value)) in shares.iter().enumerate() {
+        if value.len() != length {
+            return Err("All share values must have the same length".to_string());
+        }
+        if shares[..i].iter().any(|(xj, _)| xj == xi) {
+            return Err("Share indices must be unique".to_string());
+        }
+    }
+
+    if let Some((_, value)) = shares.iter().find(|(xi, _)| *xi == x) {
+        return Ok(value.clone());
+    }
+
+    // Logarithm of the product of (x - x_j) over all shares
+    let log_product: i32 = shares.iter().map(|(xi, _)| LOG[(xi ^ x) as usize] as i32).sum();
+
+    let mut result = vec![0u8; length];
+    for (xi, value) in shares {
+        // LOG[0] is 0, so including the share itself in `others` is harmless
+        let others: i32 = shares.iter().map(|(xj, _)| LOG[(xi ^ xj) as usize] as i32).sum();
+        let log_basis = (log_product - LOG[(xi ^ x) as usize] as i32 - others).rem_euclid(255);
+
+        for (acc, &byte) in result.iter_mut().zip(value) {
+            if byte != 0 {
+                *acc ^= EXP[((LOG[byte as usize] as i32 + log_basis) % 255) as usize];
+            }
+        }
+    }
+
+    Ok(result)
+}
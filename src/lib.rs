@@ -1,57 +1,376 @@
 use wasm_bindgen::prelude::*;
 use wasm_bindgen_futures::future_to_promise;
-use serde_wasm_bindgen;
 use bip39::{Language, Mnemonic};
 use sha2::{Sha256, Digest};
-use bs58;
-use scrypt::{scrypt, Params};
+use ripemd::Ripemd160;
+use scrypt::Params;
 use rand::rngs::OsRng;
 use rand::RngCore;
 use unicode_normalization::UnicodeNormalization;
-use js_sys::Promise;
-
-#[wasm_bindgen]
-pub fn generate_mnemonic(word_count: u32, lang: &str) -> Result<String, JsValue> {
-    let language = match lang.to_lowercase().as_str() {
-        "english" => Language::English,
-        "czech" => Language::Czech,
-        "french" => Language::French,
-        "italian" => Language::Italian,
-        "japanese" => Language::Japanese,
-        "korean" => Language::Korean,
-        "portuguese" => Language::Portuguese,
-        "spanish" => Language::Spanish,
-        _ => return Err(JsValue::from_str("Unsupported language. Supported: english, czech, french, italian, japanese, korean, portuguese, spanish.")),
+use js_sys::{Function, Promise};
+use k256::elliptic_curve::sec1::ToEncodedPoint;
+
+mod bip85;
+mod chunked_scrypt;
+mod codex32;
+mod electrum;
+mod gf256;
+mod scheme;
+mod seedqr;
+mod seedxor;
+mod shamir;
+mod slip39;
+
+#[wasm_bindgen]
+pub fn generate_mnemonic(word_count: u32, lang: &str, extra_entropy: Option<Vec<u8>>) -> Result<String, JsValue> {
+    let language = parse_language(lang)
+        .ok_or_else(|| JsValue::from_str("Unsupported language. Supported: english, chinese_simplified, chinese_traditional, czech, french, italian, japanese, korean, portuguese, spanish."))?;
+
+    let entropy_bytes = entropy_length(word_count)
+        .map(|length| generate_entropy(length, extra_entropy.as_deref()))
+        .ok_or_else(|| JsValue::from_str("Invalid word count (must be 12, 15, 18, 21, or 24)"))?;
+
+    let mnemonic = Mnemonic::from_entropy_in(language, &entropy_bytes)
+        .map_err(|_| JsValue::from_str("Failed to generate mnemonic"))?;
+
+    Ok(join_words(language, mnemonic.words()))
+}
+
+fn parse_language(lang: &str) -> Option<Language> {
+    match lang.to_lowercase().as_str() {
+        "english" => Some(Language::English),
+        "chinese_simplified" => Some(Language::SimplifiedChinese),
+        "chinese_traditional" => Some(Language::TraditionalChinese),
+        "czech" => Some(Language::Czech),
+        "french" => Some(Language::French),
+        "italian" => Some(Language::Italian),
+        "japanese" => Some(Language::Japanese),
+        "korean" => Some(Language::Korean),
+        "portuguese" => Some(Language::Portuguese),
+        "spanish" => Some(Language::Spanish),
+        _ => None,
+    }
+}
+
+fn language_name(language: Language) -> &'static str {
+    match language {
+        Language::English => "english",
+        Language::SimplifiedChinese => "chinese_simplified",
+        Language::TraditionalChinese => "chinese_traditional",
+        Language::Czech => "czech",
+        Language::French => "french",
+        Language::Italian => "italian",
+        Language::Japanese => "japanese",
+        Language::Korean => "korean",
+        Language::Portuguese => "portuguese",
+        Language::Spanish => "spanish",
+    }
+}
+
+// BIP-39 joins Japanese words with an ideographic space (U+3000)
+fn word_separator(language: Language) -> &'static str {
+    match language {
+        Language::Japanese => "\u{3000}",
+        _ => " ",
+    }
+}
+
+fn join_words<'a>(language: Language, words: impl IntoIterator<Item = &'a str>) -> String {
+    words.into_iter().collect::<Vec<_>>().join(word_separator(language))
+}
+
+fn is_chinese(language: Language) -> bool {
+    matches!(language, Language::SimplifiedChinese | Language::TraditionalChinese)
+}
+
+fn entropy_length(word_count: u32) -> Option<usize> {
+    match word_count {
+        12 => Some(16), // 128 bits
+        15 => Some(20), // 160 bits
+        18 => Some(24), // 192 bits
+        21 => Some(28), // 224 bits
+        24 => Some(32), // 256 bits
+        _ => None,
+    }
+}
+
+fn generate_entropy(length: usize, extra_entropy: Option<&[u8]>) -> Vec<u8> {
+    let Some(extra) = extra_entropy else {
+        let mut bytes = vec![0u8; length];
+        OsRng.fill_bytes(&mut bytes);
+        return bytes;
     };
 
-    let entropy_bytes = match word_count {
-        12 => generate_entropy(16), // 128 bits
-        15 => generate_entropy(20), // 160 bits
-        18 => generate_entropy(24), // 192 bits
-        21 => generate_entropy(28), // 224 bits
-        24 => generate_entropy(32), // 256 bits
-        _ => return Err(JsValue::from_str("Invalid word count (must be 12, 15, 18, 21, or 24)")),
+    // Hash a full 256 bits of OS randomness together with the caller's samples
+    // (mouse movements, dice, timings) so the result is as strong as the best source
+    let mut os_bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut os_bytes);
+
+    let digest = Sha256::new()
+        .chain_update(os_bytes)
+        .chain_update(extra)
+        .finalize();
+    digest[..length].to_vec()
+}
+
+#[wasm_bindgen]
+pub fn mnemonic_from_entropy_hex(hex: &str, lang: &str) -> Result<String, JsValue> {
+    let language = parse_language(lang)
+        .ok_or_else(|| JsValue::from_str("Unsupported language"))?;
+
+    let entropy = decode_hex(hex)
+        .ok_or_else(|| JsValue::from_str("Invalid hex string"))?;
+    if !matches!(entropy.len(), 16 | 20 | 24 | 28 | 32) {
+        return Err(JsValue::from_str("Invalid entropy length (must be 32, 40, 48, 56, or 64 hex characters)"));
+    }
+
+    let mnemonic = Mnemonic::from_entropy_in(language, &entropy)
+        .map_err(|_| JsValue::from_str("Failed to generate mnemonic"))?;
+
+    Ok(join_words(language, mnemonic.words()))
+}
+
+#[wasm_bindgen]
+pub fn entropy_to_mnemonic(entropy: &[u8], lang: &str) -> Result<String, JsValue> {
+    let language = parse_language(lang)
+        .ok_or_else(|| JsValue::from_str("Unsupported language"))?;
+
+    if !matches!(entropy.len(), 16 | 20 | 24 | 28 | 32) {
+        return Err(JsValue::from_str("Invalid entropy length (must be 16, 20, 24, 28, or 32 bytes)"));
+    }
+
+    let mnemonic = Mnemonic::from_entropy_in(language, entropy)
+        .map_err(|_| JsValue::from_str("Failed to generate mnemonic"))?;
+
+    Ok(join_words(language, mnemonic.words()))
+}
+
+// Parses in the given language, or detects it like mnemonic_to_base58_master_key
+fn parse_mnemonic_in(phrase: &str, lang: Option<&str>) -> Result<Mnemonic, JsValue> {
+    let parsed = match lang {
+        Some(lang) => {
+            let language = parse_language(lang)
+                .ok_or_else(|| JsValue::from_str("Unsupported language"))?;
+            Mnemonic::parse_in_normalized(language, &normalize_phrase(phrase))
+        }
+        None => parse_mnemonic(phrase),
     };
 
-    let mnemonic = Mnemonic::from_entropy_in(language, &entropy_bytes)
+    parsed.map_err(|_| JsValue::from_str("Invalid mnemonic"))
+}
+
+#[wasm_bindgen]
+pub fn mnemonic_to_entropy(phrase: &str, lang: Option<String>) -> Result<Vec<u8>, JsValue> {
+    let mnemonic = parse_mnemonic_in(phrase, lang.as_deref())?;
+    Ok(mnemonic_entropy(&mnemonic))
+}
+
+#[wasm_bindgen]
+pub fn mnemonic_to_entropy_hex(phrase: &str, lang: Option<String>) -> Result<String, JsValue> {
+    let mnemonic = parse_mnemonic_in(phrase, lang.as_deref())?;
+    Ok(encode_hex(&mnemonic_entropy(&mnemonic)))
+}
+
+#[derive(serde::Serialize)]
+struct MnemonicTranslation {
+    phrase: String,
+    source_language: &'static str,
+    language: &'static str,
+    // The BIP-39 seed is derived from the words, not the entropy, so a translated
+    // phrase yields a different mnemonic_to_base58_master_key output
+    seed_changed: bool,
+}
+
+#[wasm_bindgen]
+pub fn translate_mnemonic(phrase: &str, target_lang: &str, source_lang: Option<String>) -> Result<JsValue, JsValue> {
+    let target = parse_language(target_lang)
+        .ok_or_else(|| JsValue::from_str("Unsupported language"))?;
+    let mnemonic = parse_mnemonic_in(phrase, source_lang.as_deref())?;
+
+    let translated = Mnemonic::from_entropy_in(target, &mnemonic_entropy(&mnemonic))
         .map_err(|_| JsValue::from_str("Failed to generate mnemonic"))?;
 
-    Ok(mnemonic.to_string())
+    let translation = MnemonicTranslation {
+        phrase: join_words(target, translated.words()),
+        source_language: language_name(mnemonic.language()),
+        language: language_name(target),
+        seed_changed: translated.to_string() != mnemonic.to_string(),
+    };
+
+    serde_wasm_bindgen::to_value(&translation)
+        .map_err(|_| JsValue::from_str("Serialization failed"))
 }
 
-fn generate_entropy(length: usize) -> Vec<u8> {
-    let mut bytes = vec![0u8; length];
-    OsRng.fill_bytes(&mut bytes);
-    bytes
+// Each d6 roll carries log2(6) ~ 2.585 bits, rounded up to whole rolls
+fn required_dice_rolls(word_count: u32) -> Option<usize> {
+    match word_count {
+        12 => Some(50),
+        15 => Some(62),
+        18 => Some(75),
+        21 => Some(87),
+        24 => Some(100),
+        _ => None,
+    }
+}
+
+// Pearson's chi-squared statistic of the observed face counts against a uniform die
+fn chi_squared(counts: &[usize]) -> f64 {
+    let total: usize = counts.iter().sum();
+    let expected = total as f64 / counts.len() as f64;
+    counts.iter().map(|&c| (c as f64 - expected).powi(2) / expected).sum()
+}
+
+fn dice_entropy(rolls: &str, word_count: u32) -> Result<Vec<u8>, String> {
+    let length = entropy_length(word_count)
+        .ok_or("Invalid word count (must be 12, 15, 18, 21, or 24)")?;
+    let required = required_dice_rolls(word_count).unwrap_or(0);
+
+    // Rolls may be separated by whitespace or commas
+    let rolls: String = rolls.chars().filter(|c| !c.is_whitespace() && *c != ',').collect();
+    if let Some(c) = rolls.chars().find(|c| !('1'..='6').contains(c)) {
+        return Err(format!("Invalid die roll '{}' (must be 1-6)", c));
+    }
+    if rolls.len() < required {
+        return Err(format!("Not enough dice rolls: {} words need at least {}, got {}", word_count, required, rolls.len()));
+    }
+
+    // Reject rolls that are very unlikely to come from a fair die (p < 0.001, 5 dof)
+    let mut counts = [0usize; 6];
+    for c in rolls.bytes() {
+        counts[(c - b'1') as usize] += 1;
+    }
+    if chi_squared(&counts) > 20.52 {
+        return Err("Dice rolls look biased, please roll again".to_string());
+    }
+
+    // Same scheme as Coldcard: SHA-256 over the ASCII roll string
+    let digest = Sha256::digest(rolls.as_bytes());
+    Ok(digest[..length].to_vec())
+}
+
+#[wasm_bindgen]
+pub fn mnemonic_from_dice(rolls: &str, word_count: u32, lang: &str) -> Result<String, JsValue> {
+    let language = parse_language(lang)
+        .ok_or_else(|| JsValue::from_str("Unsupported language"))?;
+
+    let entropy = dice_entropy(rolls, word_count)
+        .map_err(|e| JsValue::from_str(&e))?;
+
+    let mnemonic = Mnemonic::from_entropy_in(language, &entropy)
+        .map_err(|_| JsValue::from_str("Failed to generate mnemonic"))?;
+
+    Ok(join_words(language, mnemonic.words()))
+}
+
+fn coin_flip_entropy(flips: &str) -> Result<Vec<u8>, String> {
+    // Flips are 1/0 or h/t (heads = 1), separators are ignored
+    let bits: Vec<bool> = flips
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != ',')
+        .map(|c| match c.to_ascii_lowercase() {
+            '1' | 'h' => Ok(true),
+            '0' | 't' => Ok(false),
+            _ => Err(format!("Invalid coin flip '{}' (must be 0/1 or h/t)", c)),
+        })
+        .collect::<Result<_, _>>()?;
+
+    // Every flip is used as one raw entropy bit, so the count must match exactly
+    if !matches!(bits.len(), 128 | 160 | 192 | 224 | 256) {
+        return Err(format!("Invalid number of coin flips: {} (must be 128, 160, 192, 224, or 256)", bits.len()));
+    }
+
+    // Reject flips that are very unlikely to come from a fair coin (p < 0.001, 1 dof)
+    let heads = bits.iter().filter(|&&b| b).count();
+    if chi_squared(&[heads, bits.len() - heads]) > 10.83 {
+        return Err("Coin flips look biased, please flip again".to_string());
+    }
+
+    Ok(bits
+        .chunks(8)
+        .map(|byte| byte.iter().fold(0u8, |acc, &bit| (acc << 1) | bit as u8))
+        .collect())
+}
+
+#[wasm_bindgen]
+pub fn mnemonic_from_coin_flips(flips: &str, lang: &str) -> Result<String, JsValue> {
+    let language = parse_language(lang)
+        .ok_or_else(|| JsValue::from_str("Unsupported language"))?;
+
+    let entropy = coin_flip_entropy(flips)
+        .map_err(|e| JsValue::from_str(&e))?;
+
+    let mnemonic = Mnemonic::from_entropy_in(language, &entropy)
+        .map_err(|_| JsValue::from_str("Failed to generate mnemonic"))?;
+
+    Ok(join_words(language, mnemonic.words()))
+}
+
+#[derive(serde::Deserialize, Default)]
+#[serde(default)]
+struct MasterKeyOptions {
+    // Accept unique word prefixes such as the 4-letter form on metal backups
+    expand_prefixes: bool,
+    // Clean pasted input first, see normalize_mnemonic_input
+    lenient: bool,
+    // "scrypt" (the default) or "argon2id"
+    kdf: Option<String>,
+    // scrypt cost, each defaulting to DEFAULT_SCRYPT_COST
+    log_n: Option<u8>,
+    r: Option<u32>,
+    p: Option<u32>,
+    // Argon2id cost, each defaulting to DEFAULT_ARGON2_COST
+    memory_kib: Option<u32>,
+    iterations: Option<u32>,
+    lanes: Option<u32>,
+    // Called with the completed fraction (0 to 1) while the key is derived
+    #[serde(with = "serde_wasm_bindgen::preserve")]
+    on_progress: JsValue,
+}
+
+impl MasterKeyOptions {
+    fn scheme(&self) -> Result<scheme::KeyScheme, JsValue> {
+        match self.kdf.as_deref() {
+            None | Some("scrypt") => Ok(scheme::KeyScheme::V1(ScryptCost {
+                log_n: self.log_n.unwrap_or(DEFAULT_SCRYPT_COST.log_n),
+                r: self.r.unwrap_or(DEFAULT_SCRYPT_COST.r),
+                p: self.p.unwrap_or(DEFAULT_SCRYPT_COST.p),
+            })),
+            Some("argon2id") => Ok(scheme::KeyScheme::V2(Argon2Cost {
+                memory_kib: self.memory_kib.unwrap_or(DEFAULT_ARGON2_COST.memory_kib),
+                iterations: self.iterations.unwrap_or(DEFAULT_ARGON2_COST.iterations),
+                lanes: self.lanes.unwrap_or(DEFAULT_ARGON2_COST.lanes),
+            })),
+            Some(_) => Err(JsValue::from_str("Unsupported kdf (must be scrypt or argon2id)")),
+        }
+    }
+
+    fn progress_callback(&self) -> Result<Option<&Function>, JsValue> {
+        if self.on_progress.is_undefined() || self.on_progress.is_null() {
+            return Ok(None);
+        }
+        self.on_progress
+            .dyn_ref::<Function>()
+            .map(Some)
+            .ok_or_else(|| JsValue::from_str("on_progress must be a function"))
+    }
+}
+
+fn parse_master_key_options(options: JsValue) -> Result<MasterKeyOptions, JsValue> {
+    if options.is_undefined() || options.is_null() {
+        return Ok(MasterKeyOptions::default());
+    }
+    serde_wasm_bindgen::from_value(options)
+        .map_err(|_| JsValue::from_str("Invalid options"))
 }
 
 #[wasm_bindgen]
-pub fn mnemonic_to_base58_master_key(mnemonic: &str, passphrase: &str) -> Promise {
+pub fn mnemonic_to_base58_master_key(mnemonic: &str, passphrase: &str, options: JsValue) -> Promise {
     let mnemonic_str = mnemonic.to_string();
     let passphrase_str = passphrase.to_string();
     
     future_to_promise(async move {
-        let result = generate_master_key_internal(&mnemonic_str, &passphrase_str).await;
+        let options = parse_master_key_options(options)?;
+        let result = generate_master_key_internal(&mnemonic_str, &passphrase_str, &options).await;
         match result {
             Ok(key) => Ok(JsValue::from_str(&key)),
             Err(e) => Err(e),
@@ -59,43 +378,490 @@ pub fn mnemonic_to_base58_master_key(mnemonic: &str, passphrase: &str) -> Promis
     })
 }
 
-async fn generate_master_key_internal(mnemonic: &str, passphrase: &str) -> Result<String, JsValue> {
-    let mnemonic = Mnemonic::parse_normalized(mnemonic)
+async fn generate_master_key_internal(mnemonic: &str, passphrase: &str, options: &MasterKeyOptions) -> Result<String, JsValue> {
+    let cleaned;
+    let mnemonic = if options.lenient {
+        cleaned = clean_mnemonic_input(mnemonic).phrase;
+        cleaned.as_str()
+    } else {
+        mnemonic
+    };
+
+    let parsed = if options.expand_prefixes {
+        parse_mnemonic_expanding(mnemonic)
+    } else {
+        parse_mnemonic(mnemonic)
+    };
+    let mnemonic = parsed
         .map_err(|_| JsValue::from_str("Invalid mnemonic"))?;
 
+    let derived_key = options.scheme()?.derive(&mnemonic, passphrase, None, options.progress_callback()?).await?;
+
+    Ok(encode_wif(&derived_key))
+}
+
+fn bip39_seed(mnemonic: &Mnemonic, passphrase: &str) -> [u8; 64] {
     let normalized_passphrase = passphrase.nfkd().collect::<String>();
     
     // BIP39 standard: mnemonic.to_seed() already incorporates the passphrase
-    let seed = mnemonic.to_seed(&normalized_passphrase);
+    mnemonic.to_seed(&normalized_passphrase)
+}
+
+#[derive(Clone, Copy, PartialEq)]
+struct ScryptCost {
+    log_n: u8,
+    r: u32,
+    p: u32,
+}
+
+// Reduced scrypt parameters for better web performance
+// log_n = 14 (16,384 iterations), r = 8, p = 1 - much faster while still secure.
+// Every existing key was derived with these, and they are also the minimum.
+const DEFAULT_SCRYPT_COST: ScryptCost = ScryptCost { log_n: 14, r: 8, p: 1 };
+
+// scrypt needs 128 * r * 2^log_n bytes, which has to fit in wasm memory
+const MAX_SCRYPT_MEMORY: u64 = 1 << 30;
+// Work grows linearly with p and nothing is run in parallel here
+const MAX_SCRYPT_P: u32 = 16;
+
+impl ScryptCost {
+    fn validate(&self) -> Result<(), String> {
+        let min = DEFAULT_SCRYPT_COST;
+        if self.log_n < min.log_n || self.r < min.r || self.p < min.p {
+            return Err("scrypt parameters below the minimum (log_n 14, r 8, p 1)".to_string());
+        }
+        // Parameters too large to even multiply out are over the limit as well
+        let memory = 1u64
+            .checked_shl(self.log_n as u32)
+            .and_then(|n| n.checked_mul(128 * self.r as u64));
+        if memory.is_none_or(|memory| memory > MAX_SCRYPT_MEMORY) {
+            return Err("scrypt parameters need more than 1 GiB of memory".to_string());
+        }
+        if self.p > MAX_SCRYPT_P {
+            return Err(format!("scrypt parameter p above the maximum ({})", MAX_SCRYPT_P));
+        }
+
+        Params::new(self.log_n, self.r, self.p, 32)
+            .map(|_| ())
+            .map_err(|_| "Invalid scrypt params".to_string())
+    }
+}
+
+// Use a fixed salt - the passphrase is already incorporated in the seed
+const MASTER_KEY_SALT: &[u8] = b"pixa-bip39";
 
-    // Reduced scrypt parameters for better web performance
-    // log_n = 14 (16,384 iterations), r = 8, p = 1 - much faster while still secure
-    let params = Params::new(14, 8, 1, 32)
-        .map_err(|_| JsValue::from_str("Invalid scrypt params"))?;
+async fn stretch_seed_with_salt(
+    seed: &[u8],
+    salt: &[u8],
+    cost: &ScryptCost,
+    progress: Option<&Function>,
+) -> Result<[u8; 32], JsValue> {
+    cost.validate().map_err(|e| JsValue::from_str(&e))?;
 
-    // Use a fixed salt - the passphrase is already incorporated in the seed
-    let salt = b"pixa-bip39";
     let mut derived_key = [0u8; 32];
-    
+
     // Yield control back to the browser periodically during scrypt
-    scrypt(&seed, salt, &params, &mut derived_key)
-        .map_err(|_| JsValue::from_str("Scrypt failed"))?;
+    chunked_scrypt::scrypt(seed, salt, cost, &mut derived_key, progress).await?;
+
+    Ok(derived_key)
+}
+
+#[derive(Clone, Copy, PartialEq)]
+struct Argon2Cost {
+    memory_kib: u32,
+    iterations: u32,
+    lanes: u32,
+}
+
+// OWASP's baseline for Argon2id: 19 MiB, 2 passes, 1 lane. wasm runs on a
+// single thread, so more lanes only cost memory. These are also the minimum.
+const DEFAULT_ARGON2_COST: Argon2Cost = Argon2Cost { memory_kib: 19 * 1024, iterations: 2, lanes: 1 };
+
+const MAX_ARGON2_MEMORY_KIB: u32 = 1 << 20;
+
+impl Argon2Cost {
+    fn params(&self) -> Result<argon2::Params, String> {
+        let min = DEFAULT_ARGON2_COST;
+        if self.memory_kib < min.memory_kib || self.iterations < min.iterations || self.lanes < min.lanes {
+            return Err("Argon2id parameters below the minimum (memory_kib 19456, iterations 2, lanes 1)".to_string());
+        }
+        if self.memory_kib > MAX_ARGON2_MEMORY_KIB {
+            return Err("Argon2id parameters need more than 1 GiB of memory".to_string());
+        }
+
+        argon2::Params::new(self.memory_kib, self.iterations, self.lanes, Some(32))
+            .map_err(|_| "Invalid Argon2id params".to_string())
+    }
+}
+
+// Argon2id in place of the scrypt step, over the same seed and salt
+fn stretch_seed_argon2(seed: &[u8], cost: &Argon2Cost) -> Result<[u8; 32], JsValue> {
+    let params = cost.params().map_err(|e| JsValue::from_str(&e))?;
+    let argon2 = argon2::Argon2::new(argon2::Algorithm::Argon2id, argon2::Version::V0x13, params);
+
+    let mut derived_key = [0u8; 32];
+    argon2.hash_password_into(seed, MASTER_KEY_SALT, &mut derived_key)
+        .map_err(|_| JsValue::from_str("Argon2id failed"))?;
+
+    Ok(derived_key)
+}
 
+fn encode_wif(derived_key: &[u8; 32]) -> String {
     // WIF encoding: prepend 0x80, append 0x01 + 4-byte checksum (double SHA256)
     let mut extended = vec![0x80];
-    extended.extend_from_slice(&derived_key);
+    extended.extend_from_slice(derived_key);
     extended.push(0x01);
 
-    let checksum = Sha256::digest(&Sha256::digest(&extended));
+    let checksum = Sha256::digest(Sha256::digest(&extended));
     extended.extend_from_slice(&checksum[0..4]);
 
-    let base58_wif = bs58::encode(extended).into_string();
-    Ok(base58_wif)
+    bs58::encode(extended).into_string()
+}
+
+fn normalize_phrase(phrase: &str) -> String {
+    let normalized = phrase.nfkd().collect::<String>();
+    let trimmed = normalized.trim();
+
+    // Chinese phrases are often written without separators between characters
+    let is_chinese_run = trimmed.chars().count() > 1
+        && trimmed.chars().all(|c| {
+            let word = c.to_string();
+            Language::ALL
+                .iter()
+                .any(|language| is_chinese(*language) && language.find_word(&word).is_some())
+        });
+    if is_chinese_run {
+        trimmed.chars().map(String::from).collect::<Vec<_>>().join(" ")
+    } else {
+        normalized
+    }
+}
+
+#[derive(serde::Serialize)]
+struct InputChange {
+    kind: &'static str,
+    count: usize,
+}
+
+#[derive(serde::Serialize)]
+struct NormalizedInput {
+    phrase: String,
+    changes: Vec<InputChange>,
+}
+
+// Cleans pasted phrases: exotic spaces and line breaks, list separators, word
+// numbering ("1. abandon", "2)ability"), stray punctuation and uppercase.
+// Every rule is applied in a fixed order and counted so callers can show the diff.
+fn clean_mnemonic_input(input: &str) -> NormalizedInput {
+    let mut counts = [0usize; 7];
+    const KINDS: [&str; 7] = [
+        "line_breaks",
+        "special_spaces",
+        "separators",
+        "extra_whitespace",
+        "numbering",
+        "punctuation",
+        "uppercase",
+    ];
+
+    // Each input character is counted at most once. A plain or ideographic
+    // space (the Japanese separator) between two words is the one gap that
+    // is kept; any other plain spaces are extra.
+    let mut spaced = String::with_capacity(input.len());
+    let mut plain_spaces = 0;
+    let mut after_word = false;
+    for c in input.chars() {
+        let kind = match c {
+            '\n' | '\r' => Some(0),
+            ' ' | '\u{3000}' => None,
+            c if c.is_whitespace() => Some(1),
+            ',' | ';' | '|' | '/' | '、' | '，' | '；' => Some(2),
+            _ => None,
+        };
+
+        if let Some(kind) = kind {
+            counts[kind] += 1;
+            spaced.push(' ');
+        } else if c.is_whitespace() {
+            plain_spaces += 1;
+            spaced.push(' ');
+        } else {
+            counts[3] += plain_spaces - usize::from(after_word && plain_spaces > 0);
+            plain_spaces = 0;
+            after_word = true;
+            spaced.push(c);
+        }
+    }
+    counts[3] += plain_spaces;
+
+    let tokens: Vec<&str> = spaced.split_whitespace().collect();
+
+    let mut words = Vec::new();
+    for token in tokens {
+        // Numbering is leading digits plus an optional marker such as "1." or "#1)"
+        let unnumbered = token.trim_start_matches('#').trim_start_matches(|c: char| c.is_ascii_digit());
+        let unnumbered = if unnumbered.len() < token.trim_start_matches('#').len() {
+            counts[4] += 1;
+            unnumbered.trim_start_matches(['.', ')', ':', '-'])
+        } else {
+            token
+        };
+
+        let word = unnumbered.trim_matches(|c: char| c.is_ascii_punctuation() || matches!(c, '。' | '・'));
+        if word.len() < unnumbered.len() {
+            counts[5] += 1;
+        }
+
+        let lowercase = word.to_lowercase();
+        if lowercase != word {
+            counts[6] += 1;
+        }
+
+        if !lowercase.is_empty() {
+            words.push(lowercase);
+        }
+    }
+
+    NormalizedInput {
+        phrase: words.join(" "),
+        changes: KINDS
+            .iter()
+            .zip(counts)
+            .filter(|(_, count)| *count > 0)
+            .map(|(&kind, count)| InputChange { kind, count })
+            .collect(),
+    }
+}
+
+#[wasm_bindgen]
+pub fn normalize_mnemonic_input(input: &str) -> Result<JsValue, JsValue> {
+    serde_wasm_bindgen::to_value(&clean_mnemonic_input(input))
+        .map_err(|_| JsValue::from_str("Serialization failed"))
+}
+
+fn parse_mnemonic(phrase: &str) -> Result<Mnemonic, bip39::Error> {
+    let normalized = normalize_phrase(phrase);
+
+    match Mnemonic::language_of(&normalized) {
+        Ok(language) => Mnemonic::parse_in_normalized(language, &normalized),
+        // Simplified and Traditional Chinese share most characters; any list that
+        // passes the checksum yields the same seed since the words are identical
+        Err(bip39::Error::AmbiguousLanguages(candidates)) => {
+            let mut last_err = bip39::Error::InvalidChecksum;
+            for language in candidates.iter() {
+                match Mnemonic::parse_in_normalized(language, &normalized) {
+                    Ok(mnemonic) => return Ok(mnemonic),
+                    Err(e) => last_err = e,
+                }
+            }
+            Err(last_err)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+#[derive(serde::Serialize)]
+struct AmbiguousPrefix {
+    language: &'static str,
+    index: usize,
+    prefix: String,
+    matches: Vec<&'static str>,
+}
+
+// Words that are not in the list are replaced by the single word they prefix.
+// An exact match always wins, so "act" stays "act" even though "action" exists.
+fn expand_prefixes(normalized: &str, language: Language) -> Result<String, Vec<AmbiguousPrefix>> {
+    let mut words = Vec::new();
+    let mut ambiguous = Vec::new();
+
+    for (index, word) in normalized.split_whitespace().enumerate() {
+        if language.find_word(word).is_some() {
+            words.push(word.to_string());
+            continue;
+        }
+
+        let matches: Vec<&'static str> = language
+            .word_list()
+            .iter()
+            .copied()
+            .filter(|candidate| candidate.starts_with(word))
+            .collect();
+        match matches.as_slice() {
+            [single] => words.push(single.to_string()),
+            // Unknown words are kept so parsing reports them as such
+            [] => words.push(word.to_string()),
+            _ => ambiguous.push(AmbiguousPrefix {
+                language: language_name(language),
+                index,
+                prefix: word.to_string(),
+                matches,
+            }),
+        }
+    }
+
+    if ambiguous.is_empty() {
+        Ok(words.join(" "))
+    } else {
+        Err(ambiguous)
+    }
+}
+
+fn parse_mnemonic_expanding(phrase: &str) -> Result<Mnemonic, bip39::Error> {
+    let err = match parse_mnemonic(phrase) {
+        Ok(mnemonic) => return Ok(mnemonic),
+        Err(e) => e,
+    };
+
+    let normalized = normalize_phrase(phrase);
+    Language::ALL
+        .iter()
+        .filter_map(|&language| {
+            let expanded = expand_prefixes(&normalized, language).ok()?;
+            Mnemonic::parse_in_normalized(language, &expanded).ok()
+        })
+        .next()
+        .ok_or(err)
+}
+
+#[derive(serde::Serialize)]
+struct PrefixExpansion {
+    phrase: Option<String>,
+    language: Option<&'static str>,
+    ambiguous: Vec<AmbiguousPrefix>,
+}
+
+#[wasm_bindgen]
+pub fn expand_mnemonic_prefixes(phrase: &str, lang: Option<String>) -> Result<JsValue, JsValue> {
+    let languages = match lang.as_deref() {
+        Some(lang) => vec![parse_language(lang).ok_or_else(|| JsValue::from_str("Unsupported language"))?],
+        None => Language::ALL.to_vec(),
+    };
+
+    let normalized = normalize_phrase(phrase);
+    let mut expansion = PrefixExpansion { phrase: None, language: None, ambiguous: Vec::new() };
+
+    // Report the first wordlist where every word resolves and the checksum holds
+    for language in languages {
+        match expand_prefixes(&normalized, language) {
+            Ok(expanded) if Mnemonic::parse_in_normalized(language, &expanded).is_ok() => {
+                expansion.phrase = Some(expanded);
+                expansion.language = Some(language_name(language));
+                break;
+            }
+            Ok(_) => {}
+            Err(ambiguous) => expansion.ambiguous.extend(ambiguous),
+        }
+    }
+
+    serde_wasm_bindgen::to_value(&expansion)
+        .map_err(|_| JsValue::from_str("Serialization failed"))
+}
+
+fn shortest_unique_prefix(language: Language, word: &'static str) -> &'static str {
+    let others: Vec<&str> = language.word_list().iter().copied().filter(|w| *w != word).collect();
+
+    word.char_indices()
+        .map(|(i, c)| &word[..i + c.len_utf8()])
+        .find(|prefix| !others.iter().any(|other| other.starts_with(prefix)))
+        .unwrap_or(word)
+}
+
+#[wasm_bindgen]
+pub fn abbreviate_mnemonic(phrase: &str, lang: Option<String>) -> Result<String, JsValue> {
+    let mnemonic = parse_mnemonic_in(phrase, lang.as_deref())?;
+    let language = mnemonic.language();
+
+    Ok(join_words(language, mnemonic.words().map(|word| shortest_unique_prefix(language, word))))
+}
+
+#[derive(serde::Deserialize, Default)]
+#[serde(default)]
+struct FormatOptions {
+    // Defaults to the language-correct separator
+    separator: Option<String>,
+    one_per_line: bool,
+    numbered: bool,
+}
+
+fn format_words(language: Language, words: &[&str], options: &FormatOptions) -> String {
+    let words: Vec<String> = words
+        .iter()
+        .enumerate()
+        .map(|(i, word)| if options.numbered { format!("{}. {}", i + 1, word) } else { word.to_string() })
+        .collect();
+
+    let separator = match (&options.separator, options.one_per_line) {
+        (_, true) => "\n",
+        (Some(separator), false) => separator.as_str(),
+        (None, false) => word_separator(language),
+    };
+    words.join(separator)
+}
+
+#[wasm_bindgen]
+pub fn format_mnemonic(phrase: &str, options: JsValue) -> Result<String, JsValue> {
+    let options: FormatOptions = if options.is_undefined() || options.is_null() {
+        FormatOptions::default()
+    } else {
+        serde_wasm_bindgen::from_value(options)
+            .map_err(|_| JsValue::from_str("Invalid options"))?
+    };
+
+    let mnemonic = parse_mnemonic(phrase)
+        .map_err(|_| JsValue::from_str("Invalid mnemonic"))?;
+    let words: Vec<&str> = mnemonic.words().collect();
+
+    Ok(format_words(mnemonic.language(), &words, &options))
+}
+
+fn candidate_languages(normalized: &str) -> Vec<Language> {
+    Language::ALL
+        .iter()
+        .copied()
+        .filter(|language| {
+            normalized
+                .split_whitespace()
+                .all(|word| language.find_word(word).is_some())
+        })
+        .collect()
+}
+
+#[derive(serde::Serialize)]
+struct LanguageDetection {
+    language: Option<&'static str>,
+    candidates: Vec<&'static str>,
+}
+
+#[wasm_bindgen]
+pub fn detect_mnemonic_language(phrase: &str) -> Result<JsValue, JsValue> {
+    let normalized = normalize_phrase(phrase);
+    if normalized.split_whitespace().next().is_none() {
+        return Err(JsValue::from_str("Phrase must contain at least 1 word"));
+    }
+
+    let candidates = candidate_languages(&normalized);
+
+    // With several candidates, prefer the first wordlist in which the checksum holds
+    let language = match candidates.as_slice() {
+        [single] => Some(*single),
+        _ => candidates
+            .iter()
+            .copied()
+            .find(|language| Mnemonic::parse_in_normalized(*language, &normalized).is_ok()),
+    };
+
+    let detection = LanguageDetection {
+        language: language.map(language_name),
+        candidates: candidates.into_iter().map(language_name).collect(),
+    };
+
+    serde_wasm_bindgen::to_value(&detection)
+        .map_err(|_| JsValue::from_str("Serialization failed"))
 }
 
 #[derive(serde::Serialize)]
 struct WordMatch {
-    word: String,
+    word: &'static str,
     score: u32,
 }
 
@@ -109,8 +875,8 @@ fn levenshtein_distance(s1: &str, s2: &str) -> u32 {
     let mut matrix = vec![vec![0u32; len2 + 1]; len1 + 1];
     
     // Initialize first row and column
-    for i in 0..=len1 { matrix[i][0] = i as u32; }
-    for j in 0..=len2 { matrix[0][j] = j as u32; }
+    for (i, row) in matrix.iter_mut().enumerate() { row[0] = i as u32; }
+    for (j, cell) in matrix[0].iter_mut().enumerate() { *cell = j as u32; }
     
     let s1_chars: Vec<char> = s1.chars().collect();
     let s2_chars: Vec<char> = s2.chars().collect();
@@ -161,32 +927,36 @@ fn fuzzy_score(query: &str, word: &str) -> u32 {
     300 - (distance * 10)
 }
 
-#[wasm_bindgen]
-pub fn search_mnemonic_words(query: &str, lang: &str, max_length: usize) -> Result<JsValue, JsValue> {
-    if query.trim().is_empty() {
-        return Err(JsValue::from_str("Query must be at least 1 character"));
+fn chinese_score(query: &str, word: &str) -> u32 {
+    // Exact character match gets highest score
+    if query == word {
+        return 1000;
     }
 
-    let language = match lang.to_lowercase().as_str() {
-        "english" => Language::English,
-        "czech" => Language::Czech,
-        "french" => Language::French,
-        "italian" => Language::Italian,
-        "japanese" => Language::Japanese,
-        "korean" => Language::Korean,
-        "portuguese" => Language::Portuguese,
-        "spanish" => Language::Spanish,
-        _ => return Err(JsValue::from_str("Unsupported language")),
+    // A multi-character query matches each of its characters, in order; past
+    // the 500th character a match no longer counts
+    match query.chars().position(|c| word.chars().eq([c])) {
+        Some(index) => 500usize.saturating_sub(index) as u32,
+        None => 0,
+    }
+}
+
+fn rank_words(language: Language, query: &str, max_length: usize) -> Vec<&'static str> {
+    // Chinese words are single characters, so edit distance is meaningless there
+    let score_fn: fn(&str, &str) -> u32 = if is_chinese(language) {
+        chinese_score
+    } else {
+        fuzzy_score
     };
 
     let mut matches: Vec<WordMatch> = language
         .word_list()
         .iter()
-        .filter_map(|word| {
-            let score = fuzzy_score(query, word);
+        .filter_map(|&word| {
+            let score = score_fn(query, word);
             if score > 0 {
                 Some(WordMatch {
-                    word: word.to_string(),
+                    word,
                     score,
                 })
             } else {
@@ -198,15 +968,875 @@ pub fn search_mnemonic_words(query: &str, lang: &str, max_length: usize) -> Resu
     // Sort by score (highest first), then alphabetically
     matches.sort_by(|a, b| {
         b.score.cmp(&a.score)
-            .then_with(|| a.word.cmp(&b.word))
+            .then_with(|| a.word.cmp(b.word))
     });
 
     // Take only the requested number of results
     matches.truncate(max_length);
 
     // Extract just the words for the response
-    let words: Vec<&str> = matches.iter().map(|m| m.word.as_str()).collect();
+    matches.into_iter().map(|m| m.word).collect()
+}
+
+#[wasm_bindgen]
+pub fn search_mnemonic_words(query: &str, lang: &str, max_length: usize) -> Result<JsValue, JsValue> {
+    if query.trim().is_empty() {
+        return Err(JsValue::from_str("Query must be at least 1 character"));
+    }
+
+    let language = parse_language(lang)
+        .ok_or_else(|| JsValue::from_str("Unsupported language"))?;
+
+    let words = rank_words(language, query.trim(), max_length);
+
+    serde_wasm_bindgen::to_value(&words)
+        .map_err(|_| JsValue::from_str("Serialization failed"))
+}
+
+#[derive(serde::Serialize)]
+struct UnknownWord {
+    index: usize,
+    word: String,
+    suggestions: Vec<&'static str>,
+}
+
+#[derive(serde::Serialize)]
+struct ValidationReport {
+    valid: bool,
+    word_count: usize,
+    word_count_valid: bool,
+    unknown_words: Vec<UnknownWord>,
+    // True only when the word count and every word are fine but the checksum is not
+    checksum_failed: bool,
+    // Set when the phrase is an Electrum seed ("standard", "segwit", ...) rather than BIP-39
+    electrum_seed_type: Option<&'static str>,
+}
+
+fn validate_phrase(normalized: &str, language: Language) -> ValidationReport {
+    let words: Vec<&str> = normalized.split_whitespace().collect();
+    let word_count_valid = matches!(words.len(), 12 | 15 | 18 | 21 | 24);
+
+    let unknown_words: Vec<UnknownWord> = words
+        .iter()
+        .enumerate()
+        .filter(|(_, word)| language.find_word(word).is_none())
+        .map(|(index, word)| UnknownWord {
+            index,
+            word: word.to_string(),
+            suggestions: rank_words(language, word, 3),
+        })
+        .collect();
+
+    let checksum_failed = word_count_valid
+        && unknown_words.is_empty()
+        && Mnemonic::parse_in_normalized(language, normalized).is_err();
+
+    let valid = word_count_valid && unknown_words.is_empty() && !checksum_failed;
+
+    ValidationReport {
+        valid,
+        word_count: words.len(),
+        word_count_valid,
+        unknown_words,
+        checksum_failed,
+        // Any phrase matches an Electrum prefix by chance, so only flag invalid BIP-39
+        electrum_seed_type: if valid { None } else { electrum::seed_type(normalized) },
+    }
+}
+
+#[wasm_bindgen]
+pub fn validate_mnemonic(phrase: &str, lang: &str) -> Result<JsValue, JsValue> {
+    let language = parse_language(lang)
+        .ok_or_else(|| JsValue::from_str("Unsupported language"))?;
+
+    let report = validate_phrase(&normalize_phrase(phrase), language);
+
+    serde_wasm_bindgen::to_value(&report)
+        .map_err(|_| JsValue::from_str("Serialization failed"))
+}
+
+// Packs 11-bit word indices big-endian and keeps the first `entropy_len` bytes
+fn pack_word_indices(indices: &[u16], entropy_len: usize) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(indices.len() * 11 / 8 + 1);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+
+    for &index in indices {
+        buffer = (buffer << 11) | index as u32;
+        bits += 11;
+        while bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+        }
+    }
+
+    bytes.truncate(entropy_len);
+    bytes
+}
+
+// Mnemonic::to_entropy re-detects the language and panics when the words exist
+// in several wordlists (English/French), so decode from the indices instead
+fn mnemonic_entropy(mnemonic: &Mnemonic) -> Vec<u8> {
+    let indices: Vec<u16> = mnemonic.word_indices().map(|index| index as u16).collect();
+    pack_word_indices(&indices, indices.len() / 3 * 4)
+}
+
+// Phrases made together share one wordlist, but a phrase on its own may be
+// valid in several (English/French share words). Picks the first language in
+// which `parse` accepts every phrase.
+fn parse_in_shared_language<T>(
+    normalized: &[String],
+    parse: impl Fn(&str, Language) -> Option<T>,
+) -> Option<(Language, Vec<T>)> {
+    Language::ALL.iter().find_map(|&language| {
+        let parsed = normalized
+            .iter()
+            .map(|phrase| parse(phrase, language))
+            .collect::<Option<Vec<_>>>()?;
+        Some((language, parsed))
+    })
+}
+
+fn final_word_candidates(language: Language, normalized: &str) -> Result<Vec<&'static str>, String> {
+    let words: Vec<&str> = normalized.split_whitespace().collect();
+    let word_count = words.len() + 1;
+    if !matches!(word_count, 12 | 15 | 18 | 21 | 24) {
+        return Err("Invalid word count (must be 11, 14, 17, 20, or 23)".to_string());
+    }
+    if let Some(index) = words.iter().position(|word| language.find_word(word).is_none()) {
+        return Err(format!("Unknown word at position {}", index + 1));
+    }
+
+    // The last word holds the remaining entropy bits followed by the checksum bits
+    let checksum_bits = word_count / 3;
+    let mut indices: Vec<u16> = words.iter().filter_map(|word| language.find_word(word)).collect();
+
+    let candidates = (0..1u16 << (11 - checksum_bits))
+        .map(|free_bits| {
+            indices.push(free_bits << checksum_bits);
+            let entropy = pack_word_indices(&indices, word_count / 3 * 4);
+            indices.pop();
+
+            let checksum = Sha256::digest(&entropy)[0] >> (8 - checksum_bits);
+            language.word_list()[((free_bits << checksum_bits) | checksum as u16) as usize]
+        })
+        .collect();
+
+    Ok(candidates)
+}
+
+#[wasm_bindgen]
+pub fn complete_last_word(phrase: &str, lang: &str) -> Result<JsValue, JsValue> {
+    let language = parse_language(lang)
+        .ok_or_else(|| JsValue::from_str("Unsupported language"))?;
+
+    let words = final_word_candidates(language, &normalize_phrase(phrase))
+        .map_err(|e| JsValue::from_str(&e))?;
+
+    serde_wasm_bindgen::to_value(&words)
+        .map_err(|_| JsValue::from_str("Serialization failed"))
+}
+
+#[wasm_bindgen]
+pub fn search_last_mnemonic_word(query: &str, phrase: &str, lang: &str, max_length: usize) -> Result<JsValue, JsValue> {
+    if query.trim().is_empty() {
+        return Err(JsValue::from_str("Query must be at least 1 character"));
+    }
+
+    let language = parse_language(lang)
+        .ok_or_else(|| JsValue::from_str("Unsupported language"))?;
+
+    let valid_words = final_word_candidates(language, &normalize_phrase(phrase))
+        .map_err(|e| JsValue::from_str(&e))?;
+
+    // Rank every match, then move checksum-valid words ahead while keeping score order
+    let mut words = rank_words(language, query.trim(), language.word_list().len());
+    words.sort_by_key(|word| !valid_words.contains(word));
+    words.truncate(max_length);
+
+    serde_wasm_bindgen::to_value(&words)
+        .map_err(|_| JsValue::from_str("Serialization failed"))
+}
+
+fn checksum_valid(indices: &[u16]) -> bool {
+    let checksum_bits = indices.len() / 3;
+    let entropy = pack_word_indices(indices, indices.len() / 3 * 4);
+    let mask = (1u16 << checksum_bits) - 1;
+
+    let checksum = Sha256::digest(&entropy)[0] >> (8 - checksum_bits);
+    indices[indices.len() - 1] & mask == checksum as u16
+}
+
+#[derive(serde::Serialize)]
+struct RepairCandidate {
+    phrase: String,
+    kind: &'static str,
+    position: usize,
+    cost: u32,
+}
+
+fn repair_candidates(language: Language, normalized: &str) -> Result<Vec<RepairCandidate>, String> {
+    let words: Vec<&str> = normalized.split_whitespace().collect();
+    if !matches!(words.len(), 12 | 15 | 18 | 21 | 24) {
+        return Err("Invalid word count (must be 12, 15, 18, 21, or 24)".to_string());
+    }
+
+    let unknown: Vec<usize> = (0..words.len())
+        .filter(|&i| language.find_word(words[i]).is_none())
+        .collect();
+    // A single error can only account for one unknown word
+    if unknown.len() > 1 {
+        return Ok(Vec::new());
+    }
+
+    let word_list = language.word_list();
+    let mut indices: Vec<u16> = words.iter().map(|word| language.find_word(word).unwrap_or(0)).collect();
+    // Nothing to repair, and every one-word change of a valid phrase would be
+    // listed otherwise
+    if unknown.is_empty() && checksum_valid(&indices) {
+        return Ok(Vec::new());
+    }
+    let to_phrase = |indices: &[u16]| {
+        join_words(language, indices.iter().map(|&i| word_list[i as usize]))
+    };
+
+    let mut candidates = Vec::new();
+
+    // One substituted word, either the unknown one or any position otherwise
+    let positions = if unknown.is_empty() { (0..words.len()).collect() } else { unknown.clone() };
+    for position in positions {
+        let original = indices[position];
+        for (index, word) in word_list.iter().enumerate() {
+            if unknown.is_empty() && index as u16 == original {
+                continue;
+            }
+            // A known word is only replaced by plausible miscopies, using the
+            // same cutoff as fuzzy_score; an unknown word may be anything
+            let cost = levenshtein_distance(words[position], word);
+            let max_len = std::cmp::max(words[position].chars().count(), word.chars().count()) as u32;
+            if unknown.is_empty() && cost > max_len / 2 + 1 {
+                continue;
+            }
+            indices[position] = index as u16;
+            if checksum_valid(&indices) {
+                candidates.push(RepairCandidate {
+                    phrase: to_phrase(&indices),
+                    kind: "substitution",
+                    position,
+                    cost,
+                });
+            }
+        }
+        indices[position] = original;
+    }
+
+    // One swap of adjacent words
+    if unknown.is_empty() {
+        for position in 0..words.len() - 1 {
+            if indices[position] == indices[position + 1] {
+                continue;
+            }
+            indices.swap(position, position + 1);
+            if checksum_valid(&indices) {
+                candidates.push(RepairCandidate {
+                    phrase: to_phrase(&indices),
+                    kind: "transposition",
+                    position,
+                    cost: 1,
+                });
+            }
+            indices.swap(position, position + 1);
+        }
+    }
+
+    // Cheapest edits first, transpositions before substitutions of equal cost
+    candidates.sort_by(|a, b| {
+        a.cost.cmp(&b.cost)
+            .then_with(|| b.kind.cmp(a.kind))
+            .then_with(|| a.position.cmp(&b.position))
+    });
+
+    Ok(candidates)
+}
+
+#[wasm_bindgen]
+pub fn repair_mnemonic(phrase: &str, lang: &str) -> Result<JsValue, JsValue> {
+    let language = parse_language(lang)
+        .ok_or_else(|| JsValue::from_str("Unsupported language"))?;
+
+    let candidates = repair_candidates(language, &normalize_phrase(phrase))
+        .map_err(|e| JsValue::from_str(&e))?;
+
+    serde_wasm_bindgen::to_value(&candidates)
+        .map_err(|_| JsValue::from_str("Serialization failed"))
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    let hex = hex.trim();
+    if !hex.len().is_multiple_of(2) || !hex.is_ascii() {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
+
+#[derive(serde::Serialize)]
+struct MissingWordCandidate {
+    phrase: String,
+    position: usize,
+    word: &'static str,
+}
+
+fn missing_word_candidates(language: Language, normalized: &str) -> Result<Vec<MissingWordCandidate>, String> {
+    let words: Vec<&str> = normalized.split_whitespace().collect();
+    let word_count = words.len() + 1;
+    if !matches!(word_count, 12 | 15 | 18 | 21 | 24) {
+        return Err("Invalid word count (must be 11, 14, 17, 20, or 23)".to_string());
+    }
+    if let Some(index) = words.iter().position(|word| language.find_word(word).is_none()) {
+        return Err(format!("Unknown word at position {}", index + 1));
+    }
+
+    let word_list = language.word_list();
+    let known: Vec<u16> = words.iter().filter_map(|word| language.find_word(word)).collect();
+    let mut candidates = Vec::new();
+
+    for position in 0..word_count {
+        let mut indices = known.clone();
+        indices.insert(position, 0);
+        for (index, &word) in word_list.iter().enumerate() {
+            // Next to the same word, inserting before or after gives one phrase;
+            // keep only the first, as every candidate may cost a derivation
+            if position > 0 && known[position - 1] == index as u16 {
+                continue;
+            }
+            indices[position] = index as u16;
+            if checksum_valid(&indices) {
+                candidates.push(MissingWordCandidate {
+                    phrase: join_words(language, indices.iter().map(|&i| word_list[i as usize])),
+                    position,
+                    word,
+                });
+            }
+        }
+    }
+
+    Ok(candidates)
+}
+
+#[wasm_bindgen]
+pub fn recover_missing_word(phrase: &str, lang: &str) -> Result<JsValue, JsValue> {
+    let language = parse_language(lang)
+        .ok_or_else(|| JsValue::from_str("Unsupported language"))?;
 
-    Ok(serde_wasm_bindgen::to_value(&words)
-        .map_err(|_| JsValue::from_str("Serialization failed"))?)
+    let candidates = missing_word_candidates(language, &normalize_phrase(phrase))
+        .map_err(|e| JsValue::from_str(&e))?;
+
+    serde_wasm_bindgen::to_value(&candidates)
+        .map_err(|_| JsValue::from_str("Serialization failed"))
+}
+
+enum KnownKey {
+    Private([u8; 32]),
+    Public([u8; 33]),
+}
+
+// Accepts the WIF from mnemonic_to_base58_master_key, or the matching compressed
+// public key as hex or Graphene-style base58 with an optional prefix ("PXA...")
+fn parse_known_key(key: &str) -> Option<KnownKey> {
+    let key = key.trim();
+
+    if let Ok(bytes) = bs58::decode(key).into_vec() {
+        let checksum = Sha256::digest(Sha256::digest(&bytes[..bytes.len().saturating_sub(4)]));
+        if bytes.len() == 38 && bytes[0] == 0x80 && bytes[33] == 0x01 && bytes[34..] == checksum[0..4] {
+            return bytes[1..33].try_into().ok().map(KnownKey::Private);
+        }
+    }
+
+    // A mistyped key would only fail after trying every candidate, so public
+    // keys must be points on the curve and Graphene keys carry a checksum
+    let public_key = |bytes: &[u8]| -> Option<KnownKey> {
+        k256::PublicKey::from_sec1_bytes(bytes).ok()?;
+        bytes.try_into().ok().map(KnownKey::Public)
+    };
+
+    if key.len() == 66 && let Some(bytes) = decode_hex(key) {
+        return if matches!(bytes[0], 0x02 | 0x03) { public_key(&bytes) } else { None };
+    }
+
+    let prefix_len = key.chars().take_while(|c| c.is_ascii_uppercase()).count().min(5);
+    (0..=prefix_len).find_map(|skip| {
+        let bytes = bs58::decode(&key[skip..]).into_vec().ok()?;
+        if bytes.len() != 37 || !matches!(bytes[0], 0x02 | 0x03) {
+            return None;
+        }
+        // Graphene checksums are the first 4 bytes of RIPEMD-160 over the key
+        if bytes[33..] != Ripemd160::digest(&bytes[..33])[..4] {
+            return None;
+        }
+        public_key(&bytes[..33])
+    })
+}
+
+fn matches_known_key(derived_key: &[u8; 32], known: &KnownKey) -> bool {
+    match known {
+        KnownKey::Private(key) => derived_key == key,
+        KnownKey::Public(key) => k256::SecretKey::from_slice(derived_key)
+            .map(|secret| secret.public_key().to_encoded_point(true).as_bytes() == key)
+            .unwrap_or(false),
+    }
+}
+
+// `scheme` and `account` describe how the known key was derived, see scheme.rs
+#[wasm_bindgen]
+pub fn recover_missing_word_with_key(
+    phrase: &str,
+    lang: &str,
+    passphrase: &str,
+    known_key: &str,
+    scheme: Option<String>,
+    account: Option<String>,
+) -> Promise {
+    let phrase_str = phrase.to_string();
+    let lang_str = lang.to_string();
+    let passphrase_str = passphrase.to_string();
+    let known_key_str = known_key.to_string();
+    let resolved = scheme::resolve(scheme.as_deref(), account.as_deref());
+
+    future_to_promise(async move {
+        let (scheme, account) = resolved.map_err(|e| JsValue::from_str(&e))?;
+        let language = parse_language(&lang_str)
+            .ok_or_else(|| JsValue::from_str("Unsupported language"))?;
+        let known = parse_known_key(&known_key_str)
+            .ok_or_else(|| JsValue::from_str("Invalid known key (expected WIF or public key)"))?;
+
+        let candidates = missing_word_candidates(language, &normalize_phrase(&phrase_str))
+            .map_err(|e| JsValue::from_str(&e))?;
+
+        // Every candidate costs a full derivation, so stop at the first match
+        for candidate in candidates {
+            let mnemonic = Mnemonic::parse_in_normalized(language, &candidate.phrase)
+                .map_err(|_| JsValue::from_str("Invalid mnemonic"))?;
+            let derived_key = scheme.derive(&mnemonic, &passphrase_str, account.as_ref(), None).await?;
+            if matches_known_key(&derived_key, &known) {
+                return serde_wasm_bindgen::to_value(&candidate)
+                    .map_err(|_| JsValue::from_str("Serialization failed"));
+            }
+        }
+
+        Err(JsValue::from_str("No candidate matches the known key"))
+    })
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::future::Future;
+    use std::task::{Context, Poll, Waker};
+
+    // Derivations only await next_tick, which is ready at once off wasm
+    pub(crate) fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = std::pin::pin!(future);
+        let mut context = Context::from_waker(Waker::noop());
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+                return output;
+            }
+        }
+    }
+
+    const ABANDON_11: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon";
+
+    #[test]
+    fn final_word_candidates_cover_free_bits() {
+        let candidates = final_word_candidates(Language::English, ABANDON_11).unwrap();
+        assert_eq!(candidates.len(), 128);
+        assert!(candidates.contains(&"about"));
+
+        let abandon_23 = vec!["abandon"; 23].join(" ");
+        let candidates = final_word_candidates(Language::English, &abandon_23).unwrap();
+        assert_eq!(candidates.len(), 8);
+        assert!(candidates.contains(&"art"));
+
+        for word in candidates {
+            let phrase = format!("{} {}", abandon_23, word);
+            assert!(Mnemonic::parse_in_normalized(Language::English, &phrase).is_ok());
+        }
+    }
+
+    #[test]
+    fn final_word_candidates_reject_bad_input() {
+        assert!(final_word_candidates(Language::English, "abandon abandon").is_err());
+        let unknown = ABANDON_11.replacen("abandon", "notaword", 1);
+        assert!(final_word_candidates(Language::English, &unknown).is_err());
+    }
+
+    const LEGAL_WINNER: &str = "legal winner thank year wave sausage worth useful legal winner thank yellow";
+
+    #[test]
+    fn repair_leaves_valid_phrase_alone() {
+        assert!(repair_candidates(Language::English, LEGAL_WINNER).unwrap().is_empty());
+    }
+
+    #[test]
+    fn repair_finds_substitution() {
+        let typo = LEGAL_WINNER.replace("sausage", "sausag");
+        let candidates = repair_candidates(Language::English, &typo).unwrap();
+        let found = candidates.iter().find(|c| c.phrase == LEGAL_WINNER).unwrap();
+        assert_eq!((found.kind, found.position, found.cost), ("substitution", 5, 1));
+        assert_eq!(candidates[0].phrase, LEGAL_WINNER);
+
+        let miscopied = LEGAL_WINNER.replace("worth", "north");
+        let candidates = repair_candidates(Language::English, &miscopied).unwrap();
+        let found = candidates.iter().find(|c| c.phrase == LEGAL_WINNER).unwrap();
+        assert_eq!((found.kind, found.position, found.cost), ("substitution", 6, 1));
+    }
+
+    #[test]
+    fn repair_finds_transposition() {
+        let swapped = LEGAL_WINNER.replace("wave sausage", "sausage wave");
+        assert!(Mnemonic::parse_in_normalized(Language::English, &swapped).is_err());
+        let candidates = repair_candidates(Language::English, &swapped).unwrap();
+        let found = candidates.iter().find(|c| c.phrase == LEGAL_WINNER).unwrap();
+        assert_eq!((found.kind, found.position, found.cost), ("transposition", 4, 1));
+    }
+
+    #[test]
+    fn missing_word_found_at_any_position() {
+        let words: Vec<&str> = LEGAL_WINNER.split(' ').collect();
+        for position in [0, 5, 11] {
+            let mut partial = words.clone();
+            let word = partial.remove(position);
+            let candidates = missing_word_candidates(Language::English, &partial.join(" ")).unwrap();
+            let found = candidates.iter().find(|c| c.phrase == LEGAL_WINNER).unwrap();
+            assert_eq!((found.position, found.word), (position, word));
+            // Every candidate passes the checksum, about one word in 16 per gap
+            assert!(candidates.len() > 12 * 2048 / 32 && candidates.len() < 12 * 2048 / 8);
+        }
+    }
+
+    #[test]
+    fn missing_word_candidates_are_unique() {
+        // Any of the 11 "abandon" may be the missing one, all giving one phrase
+        let partial = ABANDON_ABOUT.replacen("abandon ", "", 1);
+        let candidates = missing_word_candidates(Language::English, &partial).unwrap();
+        assert_eq!(candidates.iter().filter(|c| c.phrase == ABANDON_ABOUT).count(), 1);
+        let mut phrases: Vec<&str> = candidates.iter().map(|c| c.phrase.as_str()).collect();
+        phrases.sort_unstable();
+        phrases.dedup();
+        assert_eq!(phrases.len(), candidates.len());
+    }
+
+    fn graphene_key(public_key: &[u8]) -> String {
+        let checksum = Ripemd160::digest(public_key);
+        format!("PXA{}", bs58::encode([public_key, &checksum[..4]].concat()).into_string())
+    }
+
+    #[test]
+    fn known_keys_are_checked() {
+        let secret = k256::SecretKey::from_slice(&[0x42; 32]).unwrap();
+        let public = secret.public_key().to_encoded_point(true);
+        let derived_key: [u8; 32] = [0x42; 32];
+
+        let graphene = graphene_key(public.as_bytes());
+        for key in [encode_wif(&derived_key), encode_hex(public.as_bytes()), graphene.clone()] {
+            let known = parse_known_key(&key).unwrap();
+            assert!(matches_known_key(&derived_key, &known));
+            assert!(!matches_known_key(&[0x43; 32], &known));
+        }
+
+        // One mistyped character fails the checksum
+        let last = graphene.chars().last().unwrap();
+        let typo = format!("{}{}", &graphene[..graphene.len() - 1], if last == 'z' { 'y' } else { 'z' });
+        assert!(parse_known_key(&typo).is_none());
+
+        // Hex keys need a compressed prefix and a point on the curve
+        let hex = encode_hex(public.as_bytes());
+        assert!(parse_known_key(&format!("04{}", &hex[2..])).is_none());
+        assert!(parse_known_key(&format!("02{}", "0".repeat(64))).is_none());
+    }
+
+    #[test]
+    fn missing_word_rejects_bad_input() {
+        assert!(missing_word_candidates(Language::English, LEGAL_WINNER).is_err());
+        let unknown = ABANDON_11.replacen("abandon", "notaword", 1);
+        assert!(missing_word_candidates(Language::English, &unknown).is_err());
+    }
+
+    #[test]
+    fn dice_entropy_length_and_bias() {
+        let rolls = "123456".repeat(9);
+        let entropy = dice_entropy(&rolls, 12).unwrap();
+        assert_eq!(entropy, Sha256::digest(rolls.as_bytes())[..16].to_vec());
+        // Separators are ignored
+        let spaced: Vec<String> = rolls.chars().map(String::from).collect();
+        assert_eq!(dice_entropy(&spaced.join(", "), 12).unwrap(), entropy);
+        assert_eq!(dice_entropy(&"654321".repeat(17), 24).unwrap().len(), 32);
+
+        assert!(dice_entropy(&rolls[..49], 12).is_err());
+        assert!(dice_entropy(&"123456".repeat(16), 24).is_err());
+        assert!(dice_entropy(&rolls, 13).is_err());
+        assert!(dice_entropy(&format!("{}7", rolls), 12).is_err());
+        assert!(dice_entropy(&"6".repeat(100), 12).is_err());
+        assert!(dice_entropy(&"12".repeat(50), 12).is_err());
+    }
+
+    #[test]
+    fn coin_flip_entropy_length_and_bias() {
+        assert_eq!(coin_flip_entropy(&"10".repeat(64)).unwrap(), vec![0xaa; 16]);
+        assert_eq!(coin_flip_entropy(&"ht".repeat(128)).unwrap(), vec![0xaa; 32]);
+        assert_eq!(coin_flip_entropy(&"HHTT".repeat(40)).unwrap(), vec![0xcc; 20]);
+
+        assert!(coin_flip_entropy(&"10".repeat(63)).is_err());
+        assert!(coin_flip_entropy(&format!("{}1", "10".repeat(64))).is_err());
+        assert!(coin_flip_entropy(&"1x".repeat(64)).is_err());
+        assert!(coin_flip_entropy(&"h".repeat(128)).is_err());
+        assert!(coin_flip_entropy(&format!("{}{}", "1".repeat(90), "0".repeat(38))).is_err());
+    }
+
+    #[test]
+    fn prefixes_round_trip() {
+        let four_letter: Vec<String> = LEGAL_WINNER.split(' ').map(|w| w.chars().take(4).collect()).collect();
+        assert_eq!(expand_prefixes(&four_letter.join(" "), Language::English).ok().as_deref(), Some(LEGAL_WINNER));
+        let mnemonic = parse_mnemonic_expanding(&four_letter.join(" ")).unwrap();
+        assert_eq!(mnemonic.to_string(), LEGAL_WINNER);
+
+        let abbreviated = abbreviate_mnemonic(LEGAL_WINNER, None).unwrap();
+        assert!(abbreviated.len() < LEGAL_WINNER.len());
+        assert_eq!(expand_prefixes(&abbreviated, Language::English).ok().as_deref(), Some(LEGAL_WINNER));
+
+        // Exact words win over longer words they prefix
+        assert_eq!(shortest_unique_prefix(Language::English, "act"), "act");
+        assert_eq!(expand_prefixes("act", Language::English).ok().as_deref(), Some("act"));
+    }
+
+    #[test]
+    fn ambiguous_prefixes_are_reported() {
+        let ambiguous = expand_prefixes("legal ab winner", Language::English).err().unwrap();
+        assert_eq!(ambiguous.len(), 1);
+        assert_eq!((ambiguous[0].index, ambiguous[0].prefix.as_str()), (1, "ab"));
+        assert!(ambiguous[0].matches.contains(&"abandon") && ambiguous[0].matches.contains(&"about"));
+    }
+
+    fn change_counts(input: &NormalizedInput) -> Vec<(&'static str, usize)> {
+        input.changes.iter().map(|change| (change.kind, change.count)).collect()
+    }
+
+    #[test]
+    fn clean_input_counts_changes() {
+        let cleaned = clean_mnemonic_input("1. Legal,\n2) winner\tthank  #4:year.");
+        assert_eq!(cleaned.phrase, "legal winner thank year");
+        assert_eq!(
+            change_counts(&cleaned),
+            [
+                ("line_breaks", 1),
+                ("special_spaces", 1),
+                ("separators", 1),
+                ("extra_whitespace", 1),
+                ("numbering", 3),
+                ("punctuation", 1),
+                ("uppercase", 1),
+            ]
+        );
+
+        let cleaned = clean_mnemonic_input(" LEGAL|WINNER\r\n");
+        assert_eq!(cleaned.phrase, "legal winner");
+        assert_eq!(
+            change_counts(&cleaned),
+            [("line_breaks", 2), ("separators", 1), ("extra_whitespace", 1), ("uppercase", 2)]
+        );
+
+        // One space of the three is kept between the words
+        let cleaned = clean_mnemonic_input("legal \n winner");
+        assert_eq!(cleaned.phrase, "legal winner");
+        assert_eq!(change_counts(&cleaned), [("line_breaks", 1), ("extra_whitespace", 1)]);
+
+        // Ideographic spaces are how Japanese phrases are written
+        let cleaned = clean_mnemonic_input("あいこくしん\u{3000}あいさつ");
+        assert_eq!(cleaned.phrase, "あいこくしん あいさつ");
+        assert!(cleaned.changes.is_empty());
+
+        let cleaned = clean_mnemonic_input("\u{3000}あいこくしん\u{3000}\u{3000}あいさつ");
+        assert_eq!(change_counts(&cleaned), [("extra_whitespace", 2)]);
+
+        let cleaned = clean_mnemonic_input(LEGAL_WINNER);
+        assert_eq!(cleaned.phrase, LEGAL_WINNER);
+        assert!(cleaned.changes.is_empty());
+    }
+
+    #[test]
+    fn scrypt_cost_limits() {
+        assert!(DEFAULT_SCRYPT_COST.validate().is_ok());
+        assert!(ScryptCost { log_n: 20, r: 8, p: 4 }.validate().is_ok());
+
+        for (log_n, r, p) in [(13, 8, 1), (14, 7, 1), (14, 8, 0)] {
+            assert!(ScryptCost { log_n, r, p }.validate().unwrap_err().contains("below the minimum"));
+        }
+        // 128 * r * 2^log_n bytes, 1 GiB exactly is still allowed
+        assert!(ScryptCost { log_n: 20, r: 8, p: 1 }.validate().is_ok());
+        for (log_n, r, p) in [(21, 8, 1), (20, 9, 1), (32, 8, 1), (255, 8, 1), (31, u32::MAX, 1), (63, u32::MAX, 1)] {
+            assert!(ScryptCost { log_n, r, p }.validate().unwrap_err().contains("more than 1 GiB"));
+        }
+
+        assert!(ScryptCost { log_n: 14, r: 8, p: MAX_SCRYPT_P }.validate().is_ok());
+        for p in [MAX_SCRYPT_P + 1, u32::MAX] {
+            assert!(ScryptCost { log_n: 14, r: 8, p }.validate().unwrap_err().contains("above the maximum"));
+        }
+    }
+
+    const ABANDON_ABOUT: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    const ABANDON_ABOUT_TREZOR_V1: &str = "KzDhjbJaNVX3z3GhrcdEki4r24ZKsCTCe23aBrtrFqctMHMFjtrv";
+
+    // Every key made before scrypt parameters were configurable came from this
+    #[test]
+    fn default_scrypt_key_is_unchanged() {
+        let mnemonic = Mnemonic::parse(ABANDON_ABOUT).unwrap();
+        let params = Params::new(14, 8, 1, 32).unwrap();
+        let mut derived_key = [0u8; 32];
+        scrypt::scrypt(&bip39_seed(&mnemonic, "TREZOR"), MASTER_KEY_SALT, &params, &mut derived_key).unwrap();
+        assert_eq!(encode_wif(&derived_key), ABANDON_ABOUT_TREZOR_V1);
+    }
+
+    #[test]
+    fn chunked_scrypt_keeps_default_key() {
+        let mnemonic = Mnemonic::parse(ABANDON_ABOUT).unwrap();
+        let seed = bip39_seed(&mnemonic, "TREZOR");
+        let derived_key = block_on(stretch_seed_with_salt(&seed, MASTER_KEY_SALT, &DEFAULT_SCRYPT_COST, None)).unwrap();
+        assert_eq!(encode_wif(&derived_key), ABANDON_ABOUT_TREZOR_V1);
+    }
+
+    #[test]
+    fn argon2_cost_limits() {
+        assert!(DEFAULT_ARGON2_COST.params().is_ok());
+        assert!(Argon2Cost { memory_kib: MAX_ARGON2_MEMORY_KIB, iterations: 3, lanes: 4 }.params().is_ok());
+
+        for (memory_kib, iterations, lanes) in [(19 * 1024 - 1, 2, 1), (19 * 1024, 1, 2), (64 * 1024, 2, 0)] {
+            let cost = Argon2Cost { memory_kib, iterations, lanes };
+            assert!(cost.params().unwrap_err().contains("below the minimum"));
+        }
+        for memory_kib in [MAX_ARGON2_MEMORY_KIB + 1, u32::MAX] {
+            let cost = Argon2Cost { memory_kib, iterations: 2, lanes: 1 };
+            assert!(cost.params().unwrap_err().contains("more than 1 GiB"));
+        }
+    }
+
+    #[test]
+    fn default_argon2_key_is_unchanged() {
+        let mnemonic = Mnemonic::parse(ABANDON_ABOUT).unwrap();
+        let derived_key = stretch_seed_argon2(&bip39_seed(&mnemonic, "TREZOR"), &DEFAULT_ARGON2_COST).unwrap();
+        assert_eq!(encode_wif(&derived_key), "KzE84Fjsr1R2aTr5tHp5NzfjDN5idiiBHK5PFyuGH1pyNAznDwK6");
+    }
+
+    #[test]
+    fn chinese_runs_are_split() {
+        let mnemonic = Mnemonic::from_entropy_in(Language::SimplifiedChinese, &[0x3c; 16]).unwrap();
+        let spaced = mnemonic.to_string();
+        let run: String = spaced.split(' ').collect();
+
+        assert_eq!(normalize_phrase(&run), spaced);
+        assert_eq!(normalize_phrase(&format!("  {}\n", run)), spaced);
+        assert_eq!(parse_mnemonic(&run).unwrap().to_string(), spaced);
+        // Latin phrases are left alone
+        assert_eq!(normalize_phrase(LEGAL_WINNER), LEGAL_WINNER);
+    }
+
+    #[test]
+    fn chinese_phrase_in_both_lists_parses() {
+        // A phrase whose characters are all in both lists is ambiguous to
+        // language_of; the indices differ, so only one list may pass the checksum
+        let shared = (0..=255u8)
+            .map(|seed| Mnemonic::from_entropy_in(Language::SimplifiedChinese, &[seed; 16]).unwrap())
+            .find(|mnemonic| mnemonic.words().all(|word| Language::TraditionalChinese.find_word(word).is_some()))
+            .unwrap();
+        let phrase = shared.to_string();
+        assert!(matches!(Mnemonic::language_of(&phrase), Err(bip39::Error::AmbiguousLanguages(_))));
+
+        let parsed = parse_mnemonic(&phrase).unwrap();
+        assert_eq!(parsed.to_string(), phrase);
+        assert_eq!(mnemonic_entropy(&parsed), mnemonic_entropy(&shared));
+    }
+
+    #[test]
+    fn chinese_score_ranking() {
+        let word_list = Language::SimplifiedChinese.word_list();
+        let (first, second) = (word_list[0], word_list[1]);
+
+        assert_eq!(chinese_score(first, first), 1000);
+        let query = format!("{}{}", second, first);
+        assert_eq!(chinese_score(&query, second), 500);
+        assert_eq!(chinese_score(&query, first), 499);
+        assert_eq!(chinese_score(&query, word_list[2]), 0);
+        assert_eq!(rank_words(Language::SimplifiedChinese, &query, 5), [second, first]);
+
+        // Characters far into a long query score nothing instead of overflowing
+        let long = format!("{}{}", "x".repeat(600), first);
+        assert_eq!(chinese_score(&long, first), 0);
+        assert!(rank_words(Language::SimplifiedChinese, &long, 5).is_empty());
+    }
+
+    #[test]
+    fn entropy_round_trips() {
+        let zoo_vote = format!("{} vote", vec!["zoo"; 23].join(" "));
+        assert_eq!(mnemonic_entropy(&Mnemonic::parse(LEGAL_WINNER).unwrap()), [0x7f; 16]);
+        assert_eq!(mnemonic_entropy(&Mnemonic::parse(&zoo_vote).unwrap()), [0xff; 32]);
+
+        for length in [16, 32] {
+            for seed in [0x00, 0x5a, 0xa5, 0xff] {
+                let entropy: Vec<u8> = (0..length as u8).map(|i| i.wrapping_mul(37) ^ seed).collect();
+                let mnemonic = Mnemonic::from_entropy_in(Language::English, &entropy).unwrap();
+                let indices: Vec<u16> = mnemonic.word_indices().map(|index| index as u16).collect();
+                assert_eq!(pack_word_indices(&indices, length), entropy);
+                assert_eq!(mnemonic_entropy(&mnemonic), entropy);
+            }
+        }
+    }
+
+    // Mnemonic::to_entropy panics on these, see mnemonic_entropy
+    #[test]
+    fn entropy_of_phrase_in_english_and_french() {
+        let french = Language::French;
+        let shared: Vec<&str> = Language::English
+            .word_list()
+            .iter()
+            .copied()
+            .filter(|word| french.find_word(word).is_some())
+            .collect();
+        let partial = shared[..11].join(" ");
+        let last = final_word_candidates(Language::English, &partial)
+            .unwrap()
+            .into_iter()
+            .find(|word| french.find_word(word).is_some())
+            .unwrap();
+        let phrase = format!("{} {}", partial, last);
+
+        let mnemonic = Mnemonic::parse_in_normalized(Language::English, &phrase).unwrap();
+        let entropy = mnemonic_entropy(&mnemonic);
+        assert_eq!(Mnemonic::from_entropy_in(Language::English, &entropy).unwrap().to_string(), phrase);
+        assert_eq!(mnemonic_entropy(&parse_mnemonic(&phrase).unwrap()).len(), 16);
+    }
+
+    #[test]
+    fn mixed_entropy_lengths() {
+        for word_count in [12, 15, 18, 21, 24] {
+            let length = entropy_length(word_count).unwrap();
+            assert_eq!(generate_entropy(length, None).len(), length);
+            // 32 bytes takes the whole digest
+            assert_eq!(generate_entropy(length, Some(b"mouse moves")).len(), length);
+        }
+    }
+
+    #[test]
+    fn mixed_entropy_includes_os_randomness() {
+        let extra = b"the same dice rolls every time";
+        assert_ne!(generate_entropy(32, Some(extra)), generate_entropy(32, Some(extra)));
+        assert_ne!(generate_entropy(16, Some(&[])), generate_entropy(16, Some(&[])));
+        assert_ne!(generate_entropy(32, Some(extra)), Sha256::digest(extra).to_vec());
+    }
 }
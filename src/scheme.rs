@@ -0,0 +1,310 @@
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::future_to_promise;
+use bip39::Mnemonic;
+use js_sys::{Function, Promise};
+use unicode_normalization::UnicodeNormalization;
+
+use crate::{
+    bip39_seed, encode_wif, parse_master_key_options, parse_mnemonic, stretch_seed_argon2, stretch_seed_with_salt,
+    Argon2Cost, ScryptCost, DEFAULT_ARGON2_COST, DEFAULT_SCRYPT_COST, MASTER_KEY_SALT,
+};
+
+// Versioned master key derivation. A scheme tag names every step between the
+// mnemonic and the WIF key, so it can be stored next to an account and that
+// account's key derived again after the defaults have moved on. New schemes
+// get a new version; existing versions never change.
+//
+//   v1              scrypt(14, 8, 1) over the BIP-39 seed, salt "pixa-bip39"
+//   v1:log_n:r:p    the same with a stronger scrypt cost
+//   v2              Argon2id (19 MiB, 2 passes, 1 lane) in place of scrypt
+//   v2:m:t:p        the same with memory in KiB, passes and lanes
+//   v3              v1 salted with the Pixagram chain identifier and account name
+//   v3:log_n:r:p    the same with a stronger scrypt cost
+//
+// The account of a v3 key is not part of the tag; it is stored alongside anyway.
+// Electrum, SLIP-39 and codex32 seeds go through the same steps, with their own
+// seed in place of the BIP-39 one.
+
+#[derive(Clone, Copy)]
+pub(crate) enum KeyScheme {
+    V1(ScryptCost),
+    V2(Argon2Cost),
+    V3(ScryptCost),
+}
+
+// Part of every v3 salt, so keys for the same account name on another chain
+// differ. Changing it changes every v3 key.
+const PIXAGRAM_CHAIN_ID: &str = "pixagram";
+
+pub(crate) struct Account {
+    name: String,
+}
+
+impl Account {
+    // NFKD like the phrase and passphrase
+    pub(crate) fn new(name: &str) -> Result<Account, String> {
+        let name = name.nfkd().collect::<String>().trim().to_lowercase();
+        if name.is_empty() {
+            return Err("Account name must not be empty".to_string());
+        }
+        Ok(Account { name })
+    }
+
+    // Length-prefixed so that no two (chain, name) pairs share a salt
+    fn salt(&self) -> Vec<u8> {
+        let mut salt = MASTER_KEY_SALT.to_vec();
+        for field in [PIXAGRAM_CHAIN_ID, &self.name] {
+            salt.extend_from_slice(&(field.len() as u32).to_be_bytes());
+            salt.extend_from_slice(field.as_bytes());
+        }
+        salt
+    }
+}
+
+fn scrypt_cost(log_n: &str, r: &str, p: &str) -> Option<ScryptCost> {
+    Some(ScryptCost {
+        log_n: log_n.parse().ok()?,
+        r: r.parse().ok()?,
+        p: p.parse().ok()?,
+    })
+}
+
+pub(crate) const CURRENT_SCHEME: KeyScheme = KeyScheme::V1(DEFAULT_SCRYPT_COST);
+
+impl KeyScheme {
+    pub(crate) fn parse(tag: &str) -> Result<KeyScheme, String> {
+        let mut fields = tag.trim().split(':');
+        let version = fields.next().unwrap_or_default();
+        let params: Vec<&str> = fields.collect();
+
+        let invalid = || format!("Invalid parameters in scheme \"{}\"", tag);
+        match (version, params.as_slice()) {
+            ("v1", []) => Ok(KeyScheme::V1(DEFAULT_SCRYPT_COST)),
+            ("v1", [log_n, r, p]) => Ok(KeyScheme::V1(scrypt_cost(log_n, r, p).ok_or_else(invalid)?)),
+            ("v2", []) => Ok(KeyScheme::V2(DEFAULT_ARGON2_COST)),
+            ("v2", [memory_kib, iterations, lanes]) => Ok(KeyScheme::V2(Argon2Cost {
+                memory_kib: memory_kib.parse().map_err(|_| invalid())?,
+                iterations: iterations.parse().map_err(|_| invalid())?,
+                lanes: lanes.parse().map_err(|_| invalid())?,
+            })),
+            ("v3", []) => Ok(KeyScheme::V3(DEFAULT_SCRYPT_COST)),
+            ("v3", [log_n, r, p]) => Ok(KeyScheme::V3(scrypt_cost(log_n, r, p).ok_or_else(invalid)?)),
+            _ => Err(format!("Unknown key derivation scheme \"{}\"", tag)),
+        }
+    }
+
+    pub(crate) fn tag(&self) -> String {
+        match self {
+            KeyScheme::V1(cost) if *cost == DEFAULT_SCRYPT_COST => "v1".to_string(),
+            KeyScheme::V1(cost) => format!("v1:{}:{}:{}", cost.log_n, cost.r, cost.p),
+            KeyScheme::V2(cost) if *cost == DEFAULT_ARGON2_COST => "v2".to_string(),
+            KeyScheme::V2(cost) => format!("v2:{}:{}:{}", cost.memory_kib, cost.iterations, cost.lanes),
+            KeyScheme::V3(cost) if *cost == DEFAULT_SCRYPT_COST => "v3".to_string(),
+            KeyScheme::V3(cost) => format!("v3:{}:{}:{}", cost.log_n, cost.r, cost.p),
+        }
+    }
+
+    pub(crate) async fn derive(
+        &self,
+        mnemonic: &Mnemonic,
+        passphrase: &str,
+        account: Option<&Account>,
+        progress: Option<&Function>,
+    ) -> Result<[u8; 32], JsValue> {
+        self.stretch(&bip39_seed(mnemonic, passphrase), account, progress).await
+    }
+
+    pub(crate) async fn stretch(
+        &self,
+        seed: &[u8],
+        account: Option<&Account>,
+        progress: Option<&Function>,
+    ) -> Result<[u8; 32], JsValue> {
+        match (self, account) {
+            (KeyScheme::V1(cost), None) => stretch_seed_with_salt(seed, MASTER_KEY_SALT, cost, progress).await,
+            // Argon2id runs in one piece, so progress only reports completion
+            (KeyScheme::V2(cost), None) => {
+                let derived_key = stretch_seed_argon2(seed, cost)?;
+                if let Some(callback) = progress {
+                    callback.call1(&JsValue::NULL, &JsValue::from_f64(1.0))?;
+                }
+                Ok(derived_key)
+            }
+            (KeyScheme::V3(cost), Some(account)) => stretch_seed_with_salt(seed, &account.salt(), cost, progress).await,
+            (KeyScheme::V3(_), None) => Err(JsValue::from_str("Scheme v3 needs an account, use mnemonic_to_account_master_key")),
+            (_, Some(_)) => Err(JsValue::from_str("Only scheme v3 is salted per account")),
+        }
+    }
+}
+
+// The optional `scheme` tag and `account` name taken by every export that
+// re-derives existing keys. Keys made before schemes existed are v1, so that
+// is the default; v3 needs the account and no other scheme accepts one.
+pub(crate) fn resolve(scheme: Option<&str>, account: Option<&str>) -> Result<(KeyScheme, Option<Account>), String> {
+    let scheme = KeyScheme::parse(scheme.unwrap_or("v1"))?;
+    let account = account.map(Account::new).transpose()?;
+    match (scheme, &account) {
+        (KeyScheme::V3(_), None) => Err("Scheme v3 needs an account".to_string()),
+        (KeyScheme::V1(_) | KeyScheme::V2(_), Some(_)) => Err("Only scheme v3 is salted per account".to_string()),
+        _ => Ok((scheme, account)),
+    }
+}
+
+// The stretching and WIF steps of mnemonic_to_base58_master_key, for seeds
+// that do not come from a BIP-39 phrase (Electrum, SLIP-39, codex32)
+pub(crate) async fn stretch_to_wif(seed: &[u8], scheme: Option<&str>, account: Option<&str>) -> Result<String, JsValue> {
+    let (scheme, account) = resolve(scheme, account).map_err(|e| JsValue::from_str(&e))?;
+    let derived_key = scheme.stretch(seed, account.as_ref(), None).await?;
+    Ok(encode_wif(&derived_key))
+}
+
+#[wasm_bindgen]
+pub fn current_key_scheme() -> String {
+    CURRENT_SCHEME.tag()
+}
+
+// The tag to store for keys made by mnemonic_to_base58_master_key with `options`
+#[wasm_bindgen]
+pub fn key_scheme_tag(options: JsValue) -> Result<String, JsValue> {
+    let scheme = parse_master_key_options(options)?.scheme()?;
+    Ok(scheme.tag())
+}
+
+#[wasm_bindgen]
+pub fn derive_master_key(mnemonic: &str, passphrase: &str, scheme: &str, on_progress: Option<Function>) -> Promise {
+    let mnemonic_str = mnemonic.to_string();
+    let passphrase_str = passphrase.to_string();
+    let scheme = KeyScheme::parse(scheme);
+
+    future_to_promise(async move {
+        let scheme = scheme.map_err(|e| JsValue::from_str(&e))?;
+        let mnemonic = parse_mnemonic(&mnemonic_str)
+            .map_err(|_| JsValue::from_str("Invalid mnemonic"))?;
+
+        let derived_key = scheme.derive(&mnemonic, &passphrase_str, None, on_progress.as_ref()).await?;
+
+        Ok(JsValue::from_str(&encode_wif(&derived_key)))
+    })
+}
+
+// Scheme v3: the same mnemonic and passphrase give unrelated keys for every
+// account, so precomputation no longer pays off across users. `scheme` is a
+// v3 tag and defaults to "v3".
+#[wasm_bindgen]
+pub fn mnemonic_to_account_master_key(
+    mnemonic: &str,
+    passphrase: &str,
+    account: &str,
+    scheme: Option<String>,
+    on_progress: Option<Function>,
+) -> Promise {
+    let mnemonic_str = mnemonic.to_string();
+    let passphrase_str = passphrase.to_string();
+    let account = Account::new(account);
+    let scheme = KeyScheme::parse(scheme.as_deref().unwrap_or("v3"));
+
+    future_to_promise(async move {
+        let account = account.map_err(|e| JsValue::from_str(&e))?;
+        let scheme = scheme.map_err(|e| JsValue::from_str(&e))?;
+        let mnemonic = parse_mnemonic(&mnemonic_str)
+            .map_err(|_| JsValue::from_str("Invalid mnemonic"))?;
+
+        let derived_key = scheme
+            .derive(&mnemonic, &passphrase_str, Some(&account), on_progress.as_ref())
+            .await?;
+
+        Ok(JsValue::from_str(&encode_wif(&derived_key)))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::block_on;
+
+    const ABANDON_ABOUT: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    fn derive_wif(scheme: &str, account: Option<&str>) -> String {
+        let mnemonic = Mnemonic::parse(ABANDON_ABOUT).unwrap();
+        let account = account.map(|name| Account::new(name).unwrap());
+        let scheme = KeyScheme::parse(scheme).unwrap();
+        let derived_key = block_on(scheme.derive(&mnemonic, "TREZOR", account.as_ref(), None)).unwrap();
+        encode_wif(&derived_key)
+    }
+
+    #[test]
+    fn tags_round_trip() {
+        for tag in ["v1", "v1:15:8:1", "v2", "v2:65536:3:1", "v3", "v3:14:16:2"] {
+            assert_eq!(KeyScheme::parse(tag).unwrap().tag(), tag);
+        }
+        assert_eq!(KeyScheme::parse(" v1:14:8:1 ").unwrap().tag(), "v1");
+        for tag in ["", "v4", "v1:14:8", "v1:a:8:1", "v2:1:2:3:4", "V1"] {
+            assert!(KeyScheme::parse(tag).is_err(), "{}", tag);
+        }
+        assert_eq!(CURRENT_SCHEME.tag(), "v1");
+    }
+
+    #[test]
+    fn resolve_defaults_to_v1() {
+        let (scheme, account) = resolve(None, None).unwrap();
+        assert_eq!(scheme.tag(), "v1");
+        assert!(account.is_none());
+
+        let (scheme, account) = resolve(Some("v3:15:8:1"), Some("alice")).unwrap();
+        assert_eq!(scheme.tag(), "v3:15:8:1");
+        assert!(account.is_some());
+
+        assert!(resolve(Some("v3"), None).is_err());
+        assert!(resolve(None, Some("alice")).is_err());
+        assert!(resolve(Some("v2"), Some("alice")).is_err());
+        assert!(resolve(Some("v3"), Some(" ")).is_err());
+        assert!(resolve(Some("v9"), None).is_err());
+    }
+
+    // The same keys as before derivation went through KeyScheme, see the
+    // pinned keys in lib.rs
+    #[test]
+    fn schemes_keep_existing_keys() {
+        assert_eq!(derive_wif("v1", None), "KzDhjbJaNVX3z3GhrcdEki4r24ZKsCTCe23aBrtrFqctMHMFjtrv");
+        assert_eq!(derive_wif("v2", None), "KzE84Fjsr1R2aTr5tHp5NzfjDN5idiiBHK5PFyuGH1pyNAznDwK6");
+
+        // Non-BIP-39 seeds are stretched the same way
+        let mnemonic = Mnemonic::parse(ABANDON_ABOUT).unwrap();
+        let seed = bip39_seed(&mnemonic, "TREZOR");
+        let (scheme, account) = resolve(Some("v3"), Some("alice")).unwrap();
+        let derived_key = block_on(scheme.stretch(&seed, account.as_ref(), None)).unwrap();
+        assert_eq!(encode_wif(&derived_key), derive_wif("v3", Some("alice")));
+    }
+
+    #[test]
+    fn account_names_are_normalized() {
+        let salt = Account::new("alice").unwrap().salt();
+        assert_eq!(Account::new("  Alice\n").unwrap().salt(), salt);
+        // Fullwidth letters fold to ASCII under compatibility decomposition
+        assert_eq!(Account::new("ＡＬＩＣＥ").unwrap().salt(), salt);
+        assert_eq!(Account::new("jos\u{e9}").unwrap().salt(), Account::new("jose\u{301}").unwrap().salt());
+        assert_ne!(Account::new("bob").unwrap().salt(), salt);
+        assert!(Account::new(" \t").is_err());
+    }
+
+    #[test]
+    fn salt_includes_chain_id() {
+        let mut expected = MASTER_KEY_SALT.to_vec();
+        expected.extend_from_slice(b"\0\0\0\x08pixagram\0\0\0\x05alice");
+        assert_eq!(Account::new("alice").unwrap().salt(), expected);
+    }
+
+    #[test]
+    fn account_keys() {
+        let alice = derive_wif("v3", Some("alice"));
+        assert_eq!(alice, "L2E2Noeb4PAm5H5TTMaaNszePQDasKoDX5QTAZ3rCgChZFHLBy3m");
+        assert_eq!(derive_wif("v3", Some("Alice")), alice);
+        assert_ne!(derive_wif("v3", Some("bob")), alice);
+        assert_ne!(derive_wif("v1", None), alice);
+
+        // A custom v3 cost is derived with that cost
+        let stronger = derive_wif("v3:14:8:2", Some("alice"));
+        assert_ne!(stronger, alice);
+        assert_eq!(derive_wif(&KeyScheme::parse("v3:14:8:2").unwrap().tag(), Some("alice")), stronger);
+    }
+}
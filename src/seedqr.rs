@@ -0,0 +1,196 @@
+use wasm_bindgen::prelude::*;
+use bip39::{Language, Mnemonic};
+use qrcode::bits::Bits;
+use qrcode::{Color, EcLevel, QrCode, Version};
+
+use crate::{join_words, mnemonic_entropy, parse_language, parse_mnemonic_in};
+
+// SeedSigner's SeedQR formats for air-gapped signers:
+// - SeedQR: every word index as 4 decimal digits, numeric mode
+// - CompactSeedQR: the raw entropy bytes (no checksum), byte mode
+// Both are defined for 12 and 24 words with error correction level L.
+// https://github.com/SeedSigner/seedsigner/blob/dev/docs/seed_qr/README.md
+
+fn standard_payload(mnemonic: &Mnemonic) -> String {
+    mnemonic.word_indices().map(|index| format!("{:04}", index)).collect()
+}
+
+fn check_word_count(mnemonic: &Mnemonic) -> Result<(), String> {
+    if matches!(mnemonic.word_count(), 12 | 24) {
+        Ok(())
+    } else {
+        Err("SeedQR supports 12 or 24 word phrases".to_string())
+    }
+}
+
+// The decoded phrase defaults to English, the only list SeedQR specifies
+fn output_language(lang: Option<String>) -> Result<Language, JsValue> {
+    match lang {
+        Some(lang) => parse_language(&lang).ok_or_else(|| JsValue::from_str("Unsupported language")),
+        None => Ok(Language::English),
+    }
+}
+
+fn decode_standard(digits: &str, language: Language) -> Result<String, String> {
+    let digits = digits.trim();
+    if !digits.bytes().all(|b| b.is_ascii_digit()) || !matches!(digits.len(), 48 | 96) {
+        return Err("SeedQR payload must be 48 or 96 digits".to_string());
+    }
+
+    let words = digits
+        .as_bytes()
+        .chunks(4)
+        .map(|chunk| {
+            let index: usize = std::str::from_utf8(chunk).unwrap_or_default().parse().unwrap_or(usize::MAX);
+            language.word_list().get(index).copied()
+        })
+        .collect::<Option<Vec<_>>>()
+        .ok_or("SeedQR word index out of range")?;
+
+    let phrase = join_words(language, words);
+    Mnemonic::parse_in_normalized(language, &phrase)
+        .map_err(|_| "Invalid mnemonic".to_string())?;
+
+    Ok(phrase)
+}
+
+fn decode_compact(payload: &[u8], language: Language) -> Result<String, String> {
+    if !matches!(payload.len(), 16 | 32) {
+        return Err("CompactSeedQR payload must be 16 or 32 bytes".to_string());
+    }
+
+    let mnemonic = Mnemonic::from_entropy_in(language, payload)
+        .map_err(|_| "Failed to generate mnemonic".to_string())?;
+
+    Ok(join_words(language, mnemonic.words()))
+}
+
+#[wasm_bindgen]
+pub fn seedqr_encode(phrase: &str) -> Result<String, JsValue> {
+    let mnemonic = parse_mnemonic_in(phrase, None)?;
+    check_word_count(&mnemonic).map_err(|e| JsValue::from_str(&e))?;
+    Ok(standard_payload(&mnemonic))
+}
+
+#[wasm_bindgen]
+pub fn seedqr_decode(digits: &str, lang: Option<String>) -> Result<String, JsValue> {
+    let language = output_language(lang)?;
+    decode_standard(digits, language).map_err(|e| JsValue::from_str(&e))
+}
+
+#[wasm_bindgen]
+pub fn compact_seedqr_encode(phrase: &str) -> Result<Vec<u8>, JsValue> {
+    let mnemonic = parse_mnemonic_in(phrase, None)?;
+    check_word_count(&mnemonic).map_err(|e| JsValue::from_str(&e))?;
+    Ok(mnemonic_entropy(&mnemonic))
+}
+
+#[wasm_bindgen]
+pub fn compact_seedqr_decode(payload: &[u8], lang: Option<String>) -> Result<String, JsValue> {
+    let language = output_language(lang)?;
+    decode_compact(payload, language).map_err(|e| JsValue::from_str(&e))
+}
+
+#[derive(serde::Serialize)]
+struct QrMatrix {
+    size: usize,
+    // Row-major, true for dark modules; the 4-module quiet zone is not included
+    modules: Vec<bool>,
+}
+
+fn encode_qr(mnemonic: &Mnemonic, compact: bool) -> Result<QrCode, qrcode::types::QrError> {
+    // The versions SeedSigner uses: 21x21/25x25 compact, 25x25/29x29 standard
+    let version = match (compact, mnemonic.word_count()) {
+        (true, 12) => 1,
+        (true, _) => 2,
+        (false, 12) => 2,
+        (false, _) => 3,
+    };
+
+    let mut bits = Bits::new(Version::Normal(version));
+    if compact {
+        bits.push_byte_data(&mnemonic_entropy(mnemonic))?;
+    } else {
+        bits.push_numeric_data(standard_payload(mnemonic).as_bytes())?;
+    }
+    bits.push_terminator(EcLevel::L)?;
+
+    QrCode::with_bits(bits, EcLevel::L)
+}
+
+fn qr_matrix(mnemonic: &Mnemonic, compact: bool) -> Result<QrMatrix, String> {
+    check_word_count(mnemonic)?;
+
+    let code = encode_qr(mnemonic, compact)
+        .map_err(|_| "Failed to encode QR code".to_string())?;
+
+    Ok(QrMatrix {
+        size: code.width(),
+        modules: code.to_colors().into_iter().map(|color| color == Color::Dark).collect(),
+    })
+}
+
+#[wasm_bindgen]
+pub fn seedqr_matrix(phrase: &str, compact: bool) -> Result<JsValue, JsValue> {
+    let mnemonic = parse_mnemonic_in(phrase, None)?;
+
+    let matrix = qr_matrix(&mnemonic, compact)
+        .map_err(|e| JsValue::from_str(&e))?;
+
+    serde_wasm_bindgen::to_value(&matrix)
+        .map_err(|_| JsValue::from_str("Serialization failed"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The worked examples in SeedSigner's docs/seed_qr/README.md
+    const PHRASE_12: &str = "forum undo fragile fade shy sign arrest garment culture tube off merit";
+    const DIGITS_12: &str = "073318950739065415961602009907670428187212261116";
+    const COMPACT_12: &str = "5bbd9d71a8ec7990831aff359d426545";
+    const PHRASE_24: &str = "attack pizza motion avocado network gather crop fresh patrol unusual wild holiday candy pony ranch winter theme error hybrid van cereal salon goddess expire";
+    const DIGITS_24: &str = "011513251154012711900771041507421289190620080870026613431420201617920614089619290300152408010643";
+    const COMPACT_24: &str = "0e74b64107f94cc0ccfae6a13dcbec3662154fec67e0e00999c07892597d190a";
+
+    #[test]
+    fn standard_examples() {
+        for (phrase, digits) in [(PHRASE_12, DIGITS_12), (PHRASE_24, DIGITS_24)] {
+            assert_eq!(standard_payload(&Mnemonic::parse(phrase).unwrap()), digits);
+            assert_eq!(decode_standard(digits, Language::English).unwrap(), phrase);
+        }
+    }
+
+    #[test]
+    fn compact_examples() {
+        for (phrase, hex) in [(PHRASE_12, COMPACT_12), (PHRASE_24, COMPACT_24)] {
+            let payload = mnemonic_entropy(&Mnemonic::parse(phrase).unwrap());
+            assert_eq!(crate::encode_hex(&payload), hex);
+            assert_eq!(decode_compact(&payload, Language::English).unwrap(), phrase);
+        }
+    }
+
+    #[test]
+    fn matrix_versions() {
+        let twelve = Mnemonic::parse(PHRASE_12).unwrap();
+        let twenty_four = Mnemonic::parse(PHRASE_24).unwrap();
+        for (mnemonic, compact, size) in [(&twelve, true, 21), (&twenty_four, true, 25), (&twelve, false, 25), (&twenty_four, false, 29)] {
+            let matrix = qr_matrix(mnemonic, compact).unwrap();
+            assert_eq!(matrix.size, size);
+            assert_eq!(matrix.modules.len(), size * size);
+        }
+
+        let eighteen = Mnemonic::from_entropy(&[0x11; 24]).unwrap();
+        assert!(qr_matrix(&eighteen, true).is_err());
+    }
+
+    #[test]
+    fn invalid_payloads_are_rejected() {
+        assert!(decode_standard(&DIGITS_12[..44], Language::English).is_err());
+        assert!(decode_standard(&DIGITS_12.replacen("0733", "2048", 1), Language::English).is_err());
+        assert!(decode_standard(&DIGITS_12.replacen("0733", "073a", 1), Language::English).is_err());
+        // Swapping two words breaks the checksum
+        assert!(decode_standard(&DIGITS_12.replacen("07331895", "18950733", 1), Language::English).is_err());
+        assert!(decode_compact(&[0u8; 20], Language::English).is_err());
+    }
+}
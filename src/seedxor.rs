@@ -0,0 +1,161 @@
+use wasm_bindgen::prelude::*;
+use bip39::Mnemonic;
+
+use crate::{
+    generate_entropy, join_words, mnemonic_entropy, normalize_phrase, parse_in_shared_language, parse_mnemonic,
+    parse_mnemonic_in,
+};
+
+// Coldcard-compatible SeedXOR: every part is a full BIP-39 phrase of the same
+// length (usable as a decoy wallet) and the XOR of their entropies is the
+// original. The first parts are random, the last one closes the XOR.
+
+const MIN_PARTS: u8 = 2;
+const MAX_PARTS: u8 = 4;
+
+fn xor_into(acc: &mut [u8], bytes: &[u8]) {
+    for (a, b) in acc.iter_mut().zip(bytes) {
+        *a ^= b;
+    }
+}
+
+fn split_mnemonic(mnemonic: &Mnemonic, parts: u8) -> Result<Vec<String>, String> {
+    if !(MIN_PARTS..=MAX_PARTS).contains(&parts) {
+        return Err("Invalid number of parts (must be 2, 3, or 4)".to_string());
+    }
+    if !matches!(mnemonic.word_count(), 12 | 18 | 24) {
+        return Err("SeedXOR supports 12, 18, or 24 word phrases".to_string());
+    }
+    let language = mnemonic.language();
+
+    let mut last = mnemonic_entropy(mnemonic);
+    let mut entropies: Vec<Vec<u8>> = (1..parts)
+        .map(|_| {
+            let entropy = generate_entropy(last.len(), None);
+            xor_into(&mut last, &entropy);
+            entropy
+        })
+        .collect();
+    entropies.push(last);
+
+    entropies
+        .iter()
+        .map(|entropy| {
+            Mnemonic::from_entropy_in(language, entropy)
+                .map(|part| join_words(language, part.words()))
+                .map_err(|_| "Failed to generate mnemonic".to_string())
+        })
+        .collect()
+}
+
+fn combine_parts(parts: &[String]) -> Result<String, String> {
+    if parts.len() < MIN_PARTS as usize {
+        return Err("At least 2 parts are required".to_string());
+    }
+
+    let normalized: Vec<String> = parts.iter().map(|part| normalize_phrase(part)).collect();
+
+    let (language, mnemonics) = parse_in_shared_language(&normalized, |part, language| {
+        Mnemonic::parse_in_normalized(language, part).ok()
+    })
+    .ok_or_else(|| {
+        // Every part must pass its own checksum
+        match normalized.iter().position(|part| parse_mnemonic(part).is_err()) {
+            Some(i) => format!("Part {} is not a valid mnemonic", i + 1),
+            None => "All parts must use the same wordlist".to_string(),
+        }
+    })?;
+
+    let word_count = mnemonics[0].word_count();
+    if mnemonics.iter().any(|m| m.word_count() != word_count) {
+        return Err("All parts must have the same number of words".to_string());
+    }
+
+    let mut entropy = mnemonic_entropy(&mnemonics[0]);
+    for mnemonic in &mnemonics[1..] {
+        xor_into(&mut entropy, &mnemonic_entropy(mnemonic));
+    }
+
+    let combined = Mnemonic::from_entropy_in(language, &entropy)
+        .map_err(|_| "Failed to generate mnemonic".to_string())?;
+
+    Ok(join_words(language, combined.words()))
+}
+
+#[wasm_bindgen]
+pub fn seedxor_split(phrase: &str, parts: u8) -> Result<JsValue, JsValue> {
+    let mnemonic = parse_mnemonic_in(phrase, None)?;
+
+    let phrases = split_mnemonic(&mnemonic, parts)
+        .map_err(|e| JsValue::from_str(&e))?;
+
+    serde_wasm_bindgen::to_value(&phrases)
+        .map_err(|_| JsValue::from_str("Serialization failed"))
+}
+
+#[wasm_bindgen]
+pub fn seedxor_combine(parts: JsValue) -> Result<String, JsValue> {
+    let parts: Vec<String> = serde_wasm_bindgen::from_value(parts)
+        .map_err(|_| JsValue::from_str("Parts must be an array of strings"))?;
+
+    combine_parts(&parts).map_err(|e| JsValue::from_str(&e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The example from the Coldcard SeedXOR documentation (seedxor.com)
+    const PART_A: &str = "romance wink lottery autumn shop bring dawn tongue range crater truth ability miss spice fitness easy legal release recall obey exchange recycle dragon room";
+    const PART_B: &str = "lion misery divide hurry latin fluid camp advance illegal lab pyramid unaware eager fringe sick camera series noodle toy crowd jeans select depth lounge";
+    const PART_C: &str = "vault nominee cradle silk own frown throw leg cactus recall talent worry gadget surface shy planet purpose coffee drip few seven term squeeze educate";
+    const COMBINED: &str = "silent toe meat possible chair blossom wait occur this worth option bag nurse find fish scene bench asthma bike wage world quit primary indoor";
+
+    fn strings(parts: &[&str]) -> Vec<String> {
+        parts.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn coldcard_example() {
+        assert_eq!(combine_parts(&strings(&[PART_A, PART_B, PART_C])).unwrap(), COMBINED);
+        assert_eq!(combine_parts(&strings(&[PART_C, PART_A, PART_B])).unwrap(), COMBINED);
+    }
+
+    #[test]
+    fn split_round_trip() {
+        let phrases = [
+            "legal winner thank year wave sausage worth useful legal winner thank yellow",
+            "letter advice cage absurd amount doctor acoustic avoid letter advice cage absurd amount doctor acoustic avoid letter always",
+            COMBINED,
+        ];
+        for phrase in phrases {
+            let mnemonic = Mnemonic::parse(phrase).unwrap();
+            for parts in MIN_PARTS..=MAX_PARTS {
+                let split = split_mnemonic(&mnemonic, parts).unwrap();
+                assert_eq!(split.len(), parts as usize);
+                assert!(split.iter().all(|part| part.split(' ').count() == mnemonic.word_count()));
+                assert_eq!(combine_parts(&split).unwrap(), phrase);
+            }
+        }
+    }
+
+    #[test]
+    fn invalid_parts_are_rejected() {
+        let mnemonic = Mnemonic::parse(COMBINED).unwrap();
+        for parts in [0, 1, 5] {
+            assert!(split_mnemonic(&mnemonic, parts).is_err());
+        }
+        let fifteen = Mnemonic::from_entropy(&[0x5a; 20]).unwrap();
+        assert!(split_mnemonic(&fifteen, 2).is_err());
+
+        let bad_checksum = PART_B.replace("lounge", "lottery");
+        assert_eq!(
+            combine_parts(&strings(&[PART_A, &bad_checksum, PART_C])).unwrap_err(),
+            "Part 2 is not a valid mnemonic"
+        );
+
+        let twelve = "legal winner thank year wave sausage worth useful legal winner thank yellow";
+        assert!(combine_parts(&strings(&[PART_A, twelve])).unwrap_err().contains("same number of words"));
+        assert!(combine_parts(&strings(&[PART_A])).is_err());
+    }
+}
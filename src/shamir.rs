@@ -0,0 +1,241 @@
+use wasm_bindgen::prelude::*;
+use bip39::{Language, Mnemonic};
+
+use crate::gf256::interpolate;
+use crate::{
+    generate_entropy, join_words, mnemonic_entropy, normalize_phrase, parse_in_shared_language, parse_mnemonic_in,
+};
+
+// k-of-n Shamir sharing of the entropy behind a BIP-39 phrase. Unlike SLIP-39,
+// every share is itself a valid BIP-39 phrase of the original length, preceded
+// by one index word whose wordlist position packs (threshold - 1) << 4 | (x - 1).
+// There is no digest, so k wrong-but-consistent shares cannot be detected;
+// surplus shares are checked against the recovered polynomial instead.
+
+const MAX_SHARE_COUNT: u8 = 16;
+const SECRET_X: u8 = 0;
+
+struct EntropyShare {
+    threshold: u8,
+    x: u8,
+    entropy: Vec<u8>,
+}
+
+fn split_entropy(entropy: &[u8], threshold: u8, share_count: u8) -> Result<Vec<(u8, Vec<u8>)>, String> {
+    if threshold < 2 || threshold > share_count || share_count > MAX_SHARE_COUNT {
+        return Err(format!("Invalid threshold {} of {} shares (need 2 <= threshold <= shares <= {})", threshold, share_count, MAX_SHARE_COUNT));
+    }
+
+    // threshold - 1 random points plus the secret at x = 0 fix a random polynomial
+    let mut base: Vec<(u8, Vec<u8>)> = (1..threshold)
+        .map(|x| (x, generate_entropy(entropy.len(), None)))
+        .collect();
+    base.push((SECRET_X, entropy.to_vec()));
+
+    (1..=share_count)
+        .map(|x| Ok((x, interpolate(&base, x)?)))
+        .collect()
+}
+
+fn parse_share(normalized: &str, language: Language) -> Option<EntropyShare> {
+    let (index_word, phrase) = normalized.trim().split_once(char::is_whitespace)?;
+
+    let index = language.find_word(index_word)?;
+    if index >> 8 != 0 {
+        return None;
+    }
+    let mnemonic = Mnemonic::parse_in_normalized(language, phrase).ok()?;
+
+    Some(EntropyShare {
+        threshold: (index >> 4) as u8 + 1,
+        x: (index & 15) as u8 + 1,
+        entropy: mnemonic_entropy(&mnemonic),
+    })
+}
+
+fn combine_entropy(shares: &[EntropyShare]) -> Result<Vec<u8>, String> {
+    let Some(first) = shares.first() else {
+        return Err("No shares provided".to_string());
+    };
+    if shares.iter().any(|s| s.threshold != first.threshold || s.entropy.len() != first.entropy.len()) {
+        return Err("Shares come from different splits".to_string());
+    }
+
+    let mut points: Vec<(u8, Vec<u8>)> = Vec::new();
+    for share in shares {
+        match points.iter().find(|(x, _)| *x == share.x) {
+            Some((_, value)) if *value != share.entropy => {
+                return Err(format!("Conflicting shares with index {}", share.x));
+            }
+            Some(_) => {}
+            None => points.push((share.x, share.entropy.clone())),
+        }
+    }
+
+    let threshold = first.threshold as usize;
+    if points.len() < threshold {
+        return Err(format!("Not enough shares: {} of {} required", points.len(), threshold));
+    }
+
+    let (base, surplus) = points.split_at(threshold);
+    for (x, value) in surplus {
+        if interpolate(base, *x)? != *value {
+            return Err(format!("Share with index {} does not match the others", x));
+        }
+    }
+
+    interpolate(base, SECRET_X)
+}
+
+fn split_phrase(mnemonic: &Mnemonic, threshold: u8, share_count: u8) -> Result<Vec<String>, String> {
+    let language = mnemonic.language();
+    let points = split_entropy(&mnemonic_entropy(mnemonic), threshold, share_count)?;
+
+    points
+        .into_iter()
+        .map(|(x, entropy)| {
+            let share = Mnemonic::from_entropy_in(language, &entropy)
+                .map_err(|_| "Failed to generate mnemonic".to_string())?;
+            let index = ((threshold as usize - 1) << 4) | (x as usize - 1);
+            let index_word = language.word_list()[index];
+            Ok(join_words(language, std::iter::once(index_word).chain(share.words())))
+        })
+        .collect()
+}
+
+fn combine_phrases(shares: &[String]) -> Result<String, String> {
+    let normalized: Vec<String> = shares.iter().map(|share| normalize_phrase(share)).collect();
+
+    let (language, shares) = parse_in_shared_language(&normalized, parse_share)
+        .ok_or("Invalid share (each needs an index word and a valid mnemonic)")?;
+
+    let entropy = combine_entropy(&shares)?;
+
+    let mnemonic = Mnemonic::from_entropy_in(language, &entropy)
+        .map_err(|_| "Failed to generate mnemonic".to_string())?;
+
+    Ok(join_words(language, mnemonic.words()))
+}
+
+#[wasm_bindgen]
+pub fn split_mnemonic_shares(phrase: &str, threshold: u8, share_count: u8) -> Result<JsValue, JsValue> {
+    let mnemonic = parse_mnemonic_in(phrase, None)?;
+
+    let shares = split_phrase(&mnemonic, threshold, share_count)
+        .map_err(|e| JsValue::from_str(&e))?;
+
+    serde_wasm_bindgen::to_value(&shares)
+        .map_err(|_| JsValue::from_str("Serialization failed"))
+}
+
+#[wasm_bindgen]
+pub fn combine_mnemonic_shares(shares: JsValue) -> Result<String, JsValue> {
+    let shares: Vec<String> = serde_wasm_bindgen::from_value(shares)
+        .map_err(|_| JsValue::from_str("Shares must be an array of strings"))?;
+
+    combine_phrases(&shares).map_err(|e| JsValue::from_str(&e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ENTROPY: [u8; 16] = [0x7f; 16];
+
+    fn to_shares(points: &[(u8, Vec<u8>)], threshold: u8) -> Vec<EntropyShare> {
+        points
+            .iter()
+            .map(|(x, entropy)| EntropyShare { threshold, x: *x, entropy: entropy.clone() })
+            .collect()
+    }
+
+    #[test]
+    fn every_subset_of_threshold_recovers() {
+        let points = split_entropy(&ENTROPY, 3, 5).unwrap();
+        assert_eq!(points.len(), 5);
+
+        for a in 0..5 {
+            for b in a + 1..5 {
+                for c in b + 1..5 {
+                    let subset = [points[c].clone(), points[a].clone(), points[b].clone()];
+                    assert_eq!(combine_entropy(&to_shares(&subset, 3)).unwrap(), ENTROPY);
+                }
+            }
+        }
+        assert_eq!(combine_entropy(&to_shares(&points, 3)).unwrap(), ENTROPY);
+    }
+
+    // k - 1 shares are consistent with every secret: for any candidate S, the
+    // polynomial through them and (0, S) gives a k-th share that recovers S
+    #[test]
+    fn fewer_than_threshold_reveal_nothing() {
+        let points = split_entropy(&ENTROPY, 3, 5).unwrap();
+        let known = &points[1..3];
+
+        for candidate in [[0u8; 16], [0xff; 16], [0x7e; 16], *b"any other secret"] {
+            let mut base = known.to_vec();
+            base.push((SECRET_X, candidate.to_vec()));
+            let completing = (5, interpolate(&base, 5).unwrap());
+
+            let mut shares = known.to_vec();
+            shares.push(completing);
+            assert_eq!(combine_entropy(&to_shares(&shares, 3)).unwrap(), candidate);
+        }
+    }
+
+    #[test]
+    fn inconsistent_shares_are_rejected() {
+        let points = split_entropy(&ENTROPY, 3, 5).unwrap();
+        let mut wrong = points[3].1.clone();
+        wrong[5] ^= 0x80;
+
+        let mut conflicting = points[..3].to_vec();
+        conflicting.push((points[0].0, wrong.clone()));
+        assert!(combine_entropy(&to_shares(&conflicting, 3)).unwrap_err().starts_with("Conflicting shares"));
+
+        let mut surplus = points[..3].to_vec();
+        surplus.push((points[3].0, wrong));
+        assert!(combine_entropy(&to_shares(&surplus, 3)).unwrap_err().contains("does not match"));
+
+        let mut mixed = to_shares(&points[..3], 3);
+        mixed[1].threshold = 2;
+        assert!(combine_entropy(&mixed).unwrap_err().contains("different splits"));
+
+        assert!(combine_entropy(&to_shares(&points[..2], 3)).unwrap_err().starts_with("Not enough shares"));
+        assert!(combine_entropy(&[]).is_err());
+    }
+
+    #[test]
+    fn phrase_round_trip() {
+        let phrase = "letter advice cage absurd amount doctor acoustic avoid letter advice cage absurd amount doctor acoustic avoid letter always";
+        let mnemonic = Mnemonic::parse(phrase).unwrap();
+
+        for (threshold, share_count) in [(2, 2), (2, 3), (3, 5), (5, 7)] {
+            let shares = split_phrase(&mnemonic, threshold, share_count).unwrap();
+            assert_eq!(shares.len(), share_count as usize);
+            assert!(shares.iter().all(|share| share.split(' ').count() == mnemonic.word_count() + 1));
+
+            // The last k shares, and the first k in reverse order
+            let k = threshold as usize;
+            assert_eq!(combine_phrases(&shares[shares.len() - k..]).unwrap(), phrase);
+            let reversed: Vec<String> = shares[..k].iter().rev().cloned().collect();
+            assert_eq!(combine_phrases(&reversed).unwrap(), phrase);
+            assert!(combine_phrases(&shares[..k - 1]).is_err());
+        }
+
+        // Every share is itself a valid phrase once the index word is removed
+        let shares = split_phrase(&mnemonic, 2, 3).unwrap();
+        for share in &shares {
+            let (_, rest) = share.split_once(' ').unwrap();
+            assert!(Mnemonic::parse(rest).is_ok());
+        }
+        assert!(combine_phrases(&[shares[0].clone(), phrase.to_string()]).is_err());
+    }
+
+    #[test]
+    fn invalid_splits_are_rejected() {
+        assert!(split_entropy(&ENTROPY, 1, 3).is_err());
+        assert!(split_entropy(&ENTROPY, 4, 3).is_err());
+        assert!(split_entropy(&ENTROPY, 2, 17).is_err());
+    }
+}
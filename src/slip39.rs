@@ -0,0 +1,532 @@
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::future_to_promise;
+use sha2::Sha256;
+use hmac::{Hmac, Mac};
+use rand::rngs::OsRng;
+use rand::Rng;
+use js_sys::Promise;
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
+use crate::gf256::interpolate;
+use crate::{generate_entropy, scheme};
+
+// SLIP-39: Shamir's Secret-Sharing for Mnemonic Codes
+// https://github.com/satoshilabs/slips/blob/master/slip-0039.md
+
+const WORDLIST: &str = include_str!("slip39/wordlist.txt");
+
+const RADIX_BITS: usize = 10;
+const ID_LENGTH_BITS: usize = 15;
+const ITERATION_EXP_LENGTH_BITS: usize = 4;
+const CHECKSUM_LENGTH_WORDS: usize = 3;
+// Identifier, flags and group/member parameters take two words each
+const METADATA_LENGTH_WORDS: usize = 4 + CHECKSUM_LENGTH_WORDS;
+const MIN_STRENGTH_BITS: usize = 128;
+const MIN_MNEMONIC_LENGTH_WORDS: usize = METADATA_LENGTH_WORDS + MIN_STRENGTH_BITS.div_ceil(RADIX_BITS);
+const MAX_SHARE_COUNT: usize = 16;
+
+const BASE_ITERATION_COUNT: u32 = 10000;
+const ROUND_COUNT: u8 = 4;
+
+const SECRET_INDEX: u8 = 255;
+const DIGEST_INDEX: u8 = 254;
+const DIGEST_LENGTH_BYTES: usize = 4;
+
+fn word_list() -> &'static [&'static str] {
+    static WORDS: OnceLock<Vec<&'static str>> = OnceLock::new();
+    WORDS.get_or_init(|| WORDLIST.lines().collect())
+}
+
+fn customization_string(extendable: bool) -> &'static [u8] {
+    if extendable { b"shamir_extendable" } else { b"shamir" }
+}
+
+fn rs1024_polymod(values: impl IntoIterator<Item = u32>) -> u32 {
+    const GEN: [u32; 10] = [
+        0xE0E040, 0x1C1C080, 0x3838100, 0x7070200, 0xE0E0009,
+        0x1C0C2412, 0x38086C24, 0x3090FC48, 0x21B1F890, 0x3F3F120,
+    ];
+
+    let mut chk = 1u32;
+    for value in values {
+        let b = chk >> 20;
+        chk = ((chk & 0xFFFFF) << 10) ^ value;
+        for (i, generator) in GEN.iter().enumerate() {
+            if (b >> i) & 1 == 1 {
+                chk ^= generator;
+            }
+        }
+    }
+    chk
+}
+
+fn rs1024_create_checksum(data: &[u32], extendable: bool) -> [u32; 3] {
+    let values = customization_string(extendable)
+        .iter()
+        .map(|&b| b as u32)
+        .chain(data.iter().copied())
+        .chain([0, 0, 0]);
+    let polymod = rs1024_polymod(values) ^ 1;
+    [(polymod >> 20) & 1023, (polymod >> 10) & 1023, polymod & 1023]
+}
+
+fn rs1024_verify_checksum(data: &[u32], extendable: bool) -> bool {
+    let values = customization_string(extendable)
+        .iter()
+        .map(|&b| b as u32)
+        .chain(data.iter().copied());
+    rs1024_polymod(values) == 1
+}
+
+fn create_digest(random_data: &[u8], shared_secret: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(random_data)
+        .expect("HMAC accepts keys of any length");
+    mac.update(shared_secret);
+    mac.finalize().into_bytes()[..DIGEST_LENGTH_BYTES].to_vec()
+}
+
+fn split_secret(threshold: u8, share_count: u8, secret: &[u8]) -> Result<Vec<(u8, Vec<u8>)>, String> {
+    if threshold < 1 || threshold > share_count || share_count as usize > MAX_SHARE_COUNT {
+        return Err(format!("Invalid threshold {} of {} shares", threshold, share_count));
+    }
+
+    if threshold == 1 {
+        return Ok((0..share_count).map(|i| (i, secret.to_vec())).collect());
+    }
+
+    // threshold - 2 random shares, plus the digest and secret points fix the polynomial
+    let random_share_count = threshold - 2;
+    let mut shares: Vec<(u8, Vec<u8>)> = (0..random_share_count)
+        .map(|i| (i, generate_entropy(secret.len(), None)))
+        .collect();
+
+    let random_part = generate_entropy(secret.len() - DIGEST_LENGTH_BYTES, None);
+    let mut digest_share = create_digest(&random_part, secret);
+    digest_share.extend_from_slice(&random_part);
+
+    let mut base_shares = shares.clone();
+    base_shares.push((DIGEST_INDEX, digest_share));
+    base_shares.push((SECRET_INDEX, secret.to_vec()));
+
+    for i in random_share_count..share_count {
+        shares.push((i, interpolate(&base_shares, i)?));
+    }
+
+    Ok(shares)
+}
+
+fn recover_secret(threshold: u8, shares: &[(u8, Vec<u8>)]) -> Result<Vec<u8>, String> {
+    if threshold == 1 {
+        return shares.first().map(|(_, value)| value.clone()).ok_or("No shares provided".to_string());
+    }
+
+    let secret = interpolate(shares, SECRET_INDEX)?;
+    let digest_share = interpolate(shares, DIGEST_INDEX)?;
+    let (digest, random_part) = digest_share.split_at(DIGEST_LENGTH_BYTES);
+
+    if digest != create_digest(random_part, &secret) {
+        return Err("Invalid digest of the shared secret".to_string());
+    }
+
+    Ok(secret)
+}
+
+// Feistel network keyed by the passphrase, see "Encryption of the master secret"
+fn round_function(round: u8, passphrase: &[u8], iteration_exponent: u8, salt: &[u8], r: &[u8]) -> Vec<u8> {
+    let mut password = vec![round];
+    password.extend_from_slice(passphrase);
+    let mut round_salt = salt.to_vec();
+    round_salt.extend_from_slice(r);
+
+    let iterations = (BASE_ITERATION_COUNT << iteration_exponent) / ROUND_COUNT as u32;
+    let mut output = vec![0u8; r.len()];
+    pbkdf2::pbkdf2_hmac::<Sha256>(&password, &round_salt, iterations, &mut output);
+    output
+}
+
+fn encryption_salt(identifier: u16, extendable: bool) -> Vec<u8> {
+    if extendable {
+        return Vec::new();
+    }
+    let mut salt = b"shamir".to_vec();
+    salt.extend_from_slice(&identifier.to_be_bytes());
+    salt
+}
+
+fn feistel(secret: &[u8], passphrase: &[u8], iteration_exponent: u8, identifier: u16, extendable: bool, decrypt: bool) -> Vec<u8> {
+    let half = secret.len() / 2;
+    let mut l = secret[..half].to_vec();
+    let mut r = secret[half..].to_vec();
+    let salt = encryption_salt(identifier, extendable);
+
+    let rounds: Vec<u8> = if decrypt { (0..ROUND_COUNT).rev().collect() } else { (0..ROUND_COUNT).collect() };
+    for round in rounds {
+        let f = round_function(round, passphrase, iteration_exponent, &salt, &r);
+        let xored: Vec<u8> = l.iter().zip(&f).map(|(a, b)| a ^ b).collect();
+        l = std::mem::replace(&mut r, xored);
+    }
+
+    r.extend_from_slice(&l);
+    r
+}
+
+#[derive(Clone, PartialEq)]
+struct Share {
+    identifier: u16,
+    extendable: bool,
+    iteration_exponent: u8,
+    group_index: u8,
+    group_threshold: u8,
+    group_count: u8,
+    member_index: u8,
+    member_threshold: u8,
+    value: Vec<u8>,
+}
+
+fn bits_to_words(bits: usize) -> usize {
+    bits.div_ceil(RADIX_BITS)
+}
+
+impl Share {
+    fn to_words(&self) -> Vec<&'static str> {
+        let id_exp = ((self.identifier as u32) << (ITERATION_EXP_LENGTH_BITS + 1))
+            | ((self.extendable as u32) << ITERATION_EXP_LENGTH_BITS)
+            | self.iteration_exponent as u32;
+        let params = ((self.group_index as u32) << 16)
+            | (((self.group_threshold - 1) as u32) << 12)
+            | (((self.group_count - 1) as u32) << 8)
+            | ((self.member_index as u32) << 4)
+            | (self.member_threshold - 1) as u32;
+
+        let mut data = vec![id_exp >> 10, id_exp & 1023, params >> 10, params & 1023];
+
+        // The value is left-padded with zero bits up to a whole number of words
+        let value_words = bits_to_words(self.value.len() * 8);
+        let padding = value_words * RADIX_BITS - self.value.len() * 8;
+        let mut bits = std::iter::repeat_n(false, padding)
+            .chain(self.value.iter().flat_map(|byte| (0..8).rev().map(move |i| (byte >> i) & 1 == 1)));
+        for _ in 0..value_words {
+            data.push((0..RADIX_BITS).fold(0, |acc, _| (acc << 1) | bits.next().unwrap_or(false) as u32));
+        }
+
+        data.extend(rs1024_create_checksum(&data, self.extendable));
+        data.iter().map(|&index| word_list()[index as usize]).collect()
+    }
+
+    fn from_phrase(phrase: &str) -> Result<Share, String> {
+        let data: Vec<u32> = phrase
+            .split_whitespace()
+            .map(|word| {
+                let word = word.to_lowercase();
+                word_list()
+                    .binary_search(&word.as_str())
+                    .map(|index| index as u32)
+                    .map_err(|_| format!("Invalid mnemonic word '{}'", word))
+            })
+            .collect::<Result<_, _>>()?;
+
+        if data.len() < MIN_MNEMONIC_LENGTH_WORDS {
+            return Err(format!("Invalid mnemonic length: must be at least {} words", MIN_MNEMONIC_LENGTH_WORDS));
+        }
+
+        let padding = (RADIX_BITS * (data.len() - METADATA_LENGTH_WORDS)) % 16;
+        if padding > 8 {
+            return Err("Invalid mnemonic length".to_string());
+        }
+
+        let id_exp = (data[0] << 10) | data[1];
+        let extendable = (id_exp >> ITERATION_EXP_LENGTH_BITS) & 1 == 1;
+        if !rs1024_verify_checksum(&data, extendable) {
+            return Err("Invalid mnemonic checksum".to_string());
+        }
+
+        let value_data = &data[4..data.len() - CHECKSUM_LENGTH_WORDS];
+        let bits: Vec<bool> = value_data
+            .iter()
+            .flat_map(|word| (0..RADIX_BITS).rev().map(move |i| (word >> i) & 1 == 1))
+            .collect();
+        if bits[..padding].iter().any(|&bit| bit) {
+            return Err("Invalid mnemonic padding".to_string());
+        }
+        let value: Vec<u8> = bits[padding..]
+            .chunks(8)
+            .map(|byte| byte.iter().fold(0u8, |acc, &bit| (acc << 1) | bit as u8))
+            .collect();
+
+        let params = (data[2] << 10) | data[3];
+        let share = Share {
+            identifier: (id_exp >> (ITERATION_EXP_LENGTH_BITS + 1)) as u16,
+            extendable,
+            iteration_exponent: (id_exp & ((1 << ITERATION_EXP_LENGTH_BITS) - 1)) as u8,
+            group_index: (params >> 16) as u8,
+            group_threshold: ((params >> 12) & 15) as u8 + 1,
+            group_count: ((params >> 8) & 15) as u8 + 1,
+            member_index: ((params >> 4) & 15) as u8,
+            member_threshold: (params & 15) as u8 + 1,
+            value,
+        };
+
+        if share.group_count < share.group_threshold {
+            return Err("Invalid mnemonic: group threshold cannot be greater than group count".to_string());
+        }
+
+        Ok(share)
+    }
+}
+
+fn validate_passphrase(passphrase: &str) -> Result<(), String> {
+    if passphrase.bytes().all(|b| (32..=126).contains(&b)) {
+        Ok(())
+    } else {
+        Err("The passphrase must contain only printable ASCII characters".to_string())
+    }
+}
+
+fn split_master_secret(
+    master_secret: &[u8],
+    passphrase: &str,
+    group_threshold: u8,
+    groups: &[(u8, u8)],
+    extendable: bool,
+    iteration_exponent: u8,
+) -> Result<Vec<Vec<String>>, String> {
+    if master_secret.len() * 8 < MIN_STRENGTH_BITS || !master_secret.len().is_multiple_of(2) {
+        return Err("The master secret must be at least 128 bits and a multiple of 16 bits".to_string());
+    }
+    if iteration_exponent >= 1 << ITERATION_EXP_LENGTH_BITS {
+        return Err("Invalid iteration exponent".to_string());
+    }
+    validate_passphrase(passphrase)?;
+    if group_threshold as usize > groups.len() {
+        return Err("The group threshold cannot exceed the number of groups".to_string());
+    }
+    if groups.iter().any(|&(threshold, count)| threshold == 1 && count > 1) {
+        return Err("Creating multiple member shares with member threshold 1 is not allowed; use 1-of-1 member sharing instead".to_string());
+    }
+
+    let identifier: u16 = OsRng.gen_range(0..1 << ID_LENGTH_BITS);
+    let encrypted = feistel(master_secret, passphrase.as_bytes(), iteration_exponent, identifier, extendable, false);
+
+    let group_count = u8::try_from(groups.len()).map_err(|_| "Too many groups".to_string())?;
+    let group_shares = split_secret(group_threshold, group_count, &encrypted)?;
+
+    groups
+        .iter()
+        .zip(group_shares)
+        .map(|(&(member_threshold, member_count), (group_index, group_secret))| {
+            let members = split_secret(member_threshold, member_count, &group_secret)?;
+            Ok(members
+                .into_iter()
+                .map(|(member_index, value)| {
+                    Share {
+                        identifier,
+                        extendable,
+                        iteration_exponent,
+                        group_index,
+                        group_threshold,
+                        group_count,
+                        member_index,
+                        member_threshold,
+                        value,
+                    }
+                    .to_words()
+                    .join(" ")
+                })
+                .collect())
+        })
+        .collect()
+}
+
+fn combine_shares(phrases: &[String], passphrase: &str) -> Result<Vec<u8>, String> {
+    validate_passphrase(passphrase)?;
+
+    let shares: Vec<Share> = phrases.iter().map(|phrase| Share::from_phrase(phrase)).collect::<Result<_, _>>()?;
+    let Some(first) = shares.first() else {
+        return Err("The list of mnemonics is empty".to_string());
+    };
+
+    // Every share must come from the same split
+    let consistent = shares.iter().all(|share| {
+        share.identifier == first.identifier
+            && share.extendable == first.extendable
+            && share.iteration_exponent == first.iteration_exponent
+            && share.group_threshold == first.group_threshold
+            && share.group_count == first.group_count
+    });
+    if !consistent {
+        return Err("All mnemonics must begin with the same 2 words and share group parameters".to_string());
+    }
+
+    let mut groups: BTreeMap<u8, Vec<&Share>> = BTreeMap::new();
+    for share in &shares {
+        let group = groups.entry(share.group_index).or_default();
+        if group.iter().any(|other| other.member_index == share.member_index) {
+            if group.contains(&share) {
+                continue;
+            }
+            return Err("Invalid set of mnemonics: duplicate member index".to_string());
+        }
+        if group.iter().any(|other| other.member_threshold != share.member_threshold) {
+            return Err("Invalid set of mnemonics: all mnemonics in a group must have the same member threshold".to_string());
+        }
+        group.push(share);
+    }
+
+    if groups.len() < first.group_threshold as usize {
+        return Err(format!("Insufficient number of mnemonic groups: {} of {} required", groups.len(), first.group_threshold));
+    }
+    if groups.len() != first.group_threshold as usize {
+        return Err(format!("Wrong number of mnemonic groups: expected {}, but {} were provided", first.group_threshold, groups.len()));
+    }
+
+    let group_shares: Vec<(u8, Vec<u8>)> = groups
+        .iter()
+        .map(|(&group_index, members)| {
+            let threshold = members[0].member_threshold;
+            if members.len() != threshold as usize {
+                return Err(format!("Wrong number of mnemonics in group {}: expected {}, but {} were provided", group_index, threshold, members.len()));
+            }
+            let member_shares: Vec<(u8, Vec<u8>)> = members.iter().map(|m| (m.member_index, m.value.clone())).collect();
+            Ok((group_index, recover_secret(threshold, &member_shares)?))
+        })
+        .collect::<Result<_, String>>()?;
+
+    let encrypted = recover_secret(first.group_threshold, &group_shares)?;
+    Ok(feistel(&encrypted, passphrase.as_bytes(), first.iteration_exponent, first.identifier, first.extendable, true))
+}
+
+fn parse_shares(shares: JsValue) -> Result<Vec<String>, JsValue> {
+    serde_wasm_bindgen::from_value(shares)
+        .map_err(|_| JsValue::from_str("Shares must be an array of strings"))
+}
+
+fn parse_groups(groups: JsValue) -> Result<Vec<(u8, u8)>, JsValue> {
+    serde_wasm_bindgen::from_value(groups)
+        .map_err(|_| JsValue::from_str("Groups must be an array of [member_threshold, member_count] pairs"))
+}
+
+#[wasm_bindgen]
+pub fn split_slip39_secret(master_secret: &[u8], passphrase: &str, group_threshold: u8, groups: JsValue) -> Result<JsValue, JsValue> {
+    let groups = parse_groups(groups)?;
+
+    // New backups use the extendable format and the reference implementation's exponent
+    let shares = split_master_secret(master_secret, passphrase, group_threshold, &groups, true, 1)
+        .map_err(|e| JsValue::from_str(&e))?;
+
+    serde_wasm_bindgen::to_value(&shares)
+        .map_err(|_| JsValue::from_str("Serialization failed"))
+}
+
+#[wasm_bindgen]
+pub fn generate_slip39_shares(strength_bits: u32, passphrase: &str, group_threshold: u8, groups: JsValue) -> Result<JsValue, JsValue> {
+    if !matches!(strength_bits, 128 | 256) {
+        return Err(JsValue::from_str("Invalid strength (must be 128 or 256 bits)"));
+    }
+    let master_secret = generate_entropy(strength_bits as usize / 8, None);
+
+    split_slip39_secret(&master_secret, passphrase, group_threshold, groups)
+}
+
+#[wasm_bindgen]
+pub fn combine_slip39_shares(shares: JsValue, passphrase: &str) -> Result<Vec<u8>, JsValue> {
+    combine_shares(&parse_shares(shares)?, passphrase)
+        .map_err(|e| JsValue::from_str(&e))
+}
+
+#[wasm_bindgen]
+pub fn slip39_to_base58_master_key(
+    shares: JsValue,
+    passphrase: &str,
+    scheme: Option<String>,
+    account: Option<String>,
+) -> Promise {
+    let passphrase_str = passphrase.to_string();
+
+    future_to_promise(async move {
+        let master_secret = combine_shares(&parse_shares(shares)?, &passphrase_str)
+            .map_err(|e| JsValue::from_str(&e))?;
+
+        // SLIP-39 master secrets take the place of the BIP-39 seed
+        let wif = scheme::stretch_to_wif(&master_secret, scheme.as_deref(), account.as_deref()).await?;
+
+        Ok(JsValue::from_str(&wif))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // From the SLIP-0039 test vectors, all with passphrase "TREZOR"
+    fn combine(phrases: &[&str]) -> Result<String, String> {
+        let phrases: Vec<String> = phrases.iter().map(|s| s.to_string()).collect();
+        combine_shares(&phrases, "TREZOR").map(|secret| crate::encode_hex(&secret))
+    }
+
+    #[test]
+    fn valid_vectors() {
+        // 1. Valid mnemonic without sharing
+        assert_eq!(
+            combine(&["duckling enlarge academic academic agency result length solution fridge kidney coal piece deal husband erode duke ajar critical decision keyboard"]),
+            Ok("bb54aac4b89dc868ba37d9cc21b2cece".to_string())
+        );
+        // 4. Basic sharing 2-of-3
+        assert_eq!(
+            combine(&[
+                "shadow pistol academic always adequate wildlife fancy gross oasis cylinder mustang wrist rescue view short owner flip making coding armed",
+                "shadow pistol academic acid actress prayer class unknown daughter sweater depict flip twice unkind craft early superior advocate guest smoking",
+            ]),
+            Ok("b43ceb7e57a0ea8766221624d01b0864".to_string())
+        );
+        // 17. Threshold number of groups and members in each group
+        assert_eq!(
+            combine(&[
+                "eraser senior decision roster beard treat identify grumpy salt index fake aviation theater cubic bike cause research dragon emphasis counter",
+                "eraser senior ceramic snake clay various huge numb argue hesitate auction category timber browser greatest hanger petition script leaf pickup",
+                "eraser senior ceramic shaft dynamic become junior wrist silver peasant force math alto coal amazing segment yelp velvet image paces",
+                "eraser senior ceramic round column hawk trust auction smug shame alive greatest sheriff living perfect corner chest sled fumes adequate",
+                "eraser senior decision smug corner ruin rescue cubic angel tackle skin skunk program roster trash rumor slush angel flea amazing",
+            ]),
+            Ok("7c3397a292a5941682d7a4ae2d898d11".to_string())
+        );
+    }
+
+    #[test]
+    fn invalid_vectors() {
+        // 2. Invalid checksum
+        assert!(combine(&["duckling enlarge academic academic agency result length solution fridge kidney coal piece deal husband erode duke ajar critical decision kidney"]).is_err());
+        // 3. Invalid padding
+        assert!(combine(&["duckling enlarge academic academic email result length solution fridge kidney coal piece deal husband erode duke ajar music cargo fitness"]).is_err());
+        // 9. Mismatching group counts
+        assert!(combine(&[
+            "average senior academic leaf broken teacher expect surface hour capture obesity desire negative dynamic dominant pistol mineral mailman iris aide",
+            "average senior academic agency curious pants blimp spew clothes slice script dress wrap firm shaft regular slavery negative theater roster",
+        ]).is_err());
+        // 15. Insufficient number of groups
+        assert!(combine(&[
+            "eraser senior decision scared cargo theory device idea deliver modify curly include pancake both news skin realize vitamins away join",
+            "eraser senior decision roster beard treat identify grumpy salt index fake aviation theater cubic bike cause research dragon emphasis counter",
+        ]).is_err());
+    }
+
+    #[test]
+    fn group_split_round_trip() {
+        let secret: Vec<u8> = (0..16).collect();
+        let groups = split_master_secret(&secret, "TREZOR", 2, &[(2, 3), (1, 1), (2, 2)], true, 0).unwrap();
+        assert_eq!(groups.iter().map(Vec::len).collect::<Vec<_>>(), [3, 1, 2]);
+
+        let pick = |shares: &[&String]| shares.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let recovered = combine_shares(&pick(&[&groups[0][2], &groups[0][0], &groups[2][1], &groups[2][0]]), "TREZOR").unwrap();
+        assert_eq!(recovered, secret);
+        let recovered = combine_shares(&pick(&[&groups[1][0], &groups[0][1], &groups[0][2]]), "TREZOR").unwrap();
+        assert_eq!(recovered, secret);
+
+        // The passphrase is not checked, it just decrypts to another secret
+        let other = combine_shares(&pick(&[&groups[1][0], &groups[2][0], &groups[2][1]]), "").unwrap();
+        assert_ne!(other, secret);
+
+        assert!(combine_shares(&pick(&[&groups[1][0]]), "TREZOR").is_err());
+        assert!(combine_shares(&pick(&[&groups[1][0], &groups[0][1]]), "TREZOR").is_err());
+    }
+}